@@ -0,0 +1,209 @@
+//! Coordinate compression for geometry which is far too large to store densely.
+//!
+//! Every interesting coordinate along an axis becomes a breakpoint, and the runs of
+//! uninteresting coordinates between breakpoints are collapsed into a single cell
+//! which remembers how many real units it covers.
+
+use super::grid::DenseGrid;
+use super::point::Point;
+
+/// A sorted set of breakpoints along one axis. Compressed cell `i` covers the real
+/// half-open range `breaks[i]..breaks[i + 1]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedAxis {
+    breaks: Vec<i64>,
+}
+
+impl CompressedAxis {
+    /// Compress an axis such that every coordinate in `coordinates` gets a cell of
+    /// its own, with one extra cell of margin at either end.
+    pub fn new<C: IntoIterator<Item = i64>>(coordinates: C) -> Self {
+        let mut breaks = coordinates
+            .into_iter()
+            .flat_map(|c| [c, c + 1])
+            .collect::<Vec<_>>();
+        assert!(!breaks.is_empty(), "cannot compress an empty axis");
+        breaks.sort_unstable();
+        breaks.dedup();
+        let (first, last) = (breaks[0], breaks[breaks.len() - 1]);
+        breaks.insert(0, first - 1);
+        breaks.push(last + 1);
+        CompressedAxis { breaks }
+    }
+
+    /// The number of compressed cells
+    pub fn len(&self) -> usize {
+        self.breaks.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The compressed cell containing a real coordinate, if it is in range
+    pub fn index_of(&self, real: i64) -> Option<usize> {
+        if real < self.breaks[0] || real >= self.breaks[self.breaks.len() - 1] {
+            return None;
+        }
+        match self.breaks.binary_search(&real) {
+            Ok(i) => Some(i),
+            Err(i) => Some(i - 1),
+        }
+    }
+
+    /// The first real coordinate covered by a compressed cell
+    pub fn start(&self, index: usize) -> i64 {
+        self.breaks[index]
+    }
+
+    /// The number of real coordinates covered by a compressed cell
+    pub fn size(&self, index: usize) -> i64 {
+        self.breaks[index + 1] - self.breaks[index]
+    }
+}
+
+/// A [`DenseGrid`] over compressed coordinates. The grid itself is indexed by
+/// compressed points starting at (0, 0); use [`CompressedGrid::compress`] to get
+/// there from real coordinates.
+#[derive(Debug, Clone)]
+pub struct CompressedGrid<V: Clone + std::fmt::Debug> {
+    pub xs: CompressedAxis,
+    pub ys: CompressedAxis,
+    pub grid: DenseGrid<V>,
+}
+
+impl<V: Clone + std::fmt::Debug> CompressedGrid<V> {
+    /// Build a grid in which every one of `points` has a cell to itself, filled
+    /// with `empty_value`. There is always at least one cell of margin around the
+    /// outside, so flood-filling from (0, 0) reaches everything exterior.
+    pub fn new<P: IntoIterator<Item = Point>>(points: P, empty_value: V) -> Self {
+        let points = points.into_iter().collect::<Vec<_>>();
+        let xs = CompressedAxis::new(points.iter().map(|p| p.x));
+        let ys = CompressedAxis::new(points.iter().map(|p| p.y));
+        let grid = DenseGrid::new_with(
+            Point::new(0, 0),
+            Point::new(xs.len() as i64 - 1, ys.len() as i64 - 1),
+            empty_value,
+        );
+        CompressedGrid { xs, ys, grid }
+    }
+
+    /// Translate a real point to the compressed cell containing it
+    pub fn compress(&self, real: Point) -> Option<Point> {
+        Some(Point::new(
+            self.xs.index_of(real.x)? as i64,
+            self.ys.index_of(real.y)? as i64,
+        ))
+    }
+
+    /// The real coordinate of the upper-left corner of a compressed cell
+    pub fn real_origin(&self, cell: Point) -> Point {
+        Point::new(
+            self.xs.start(cell.x as usize),
+            self.ys.start(cell.y as usize),
+        )
+    }
+
+    /// The real width and height of a compressed cell
+    pub fn cell_size(&self, cell: Point) -> Point {
+        Point::new(self.xs.size(cell.x as usize), self.ys.size(cell.y as usize))
+    }
+
+    /// The real area covered by a compressed cell
+    pub fn cell_area(&self, cell: Point) -> i64 {
+        let size = self.cell_size(cell);
+        size.x * size.y
+    }
+
+    /// Set every cell along the axis-aligned line between two real points
+    pub fn fill_line(&mut self, from: Point, to: Point, value: V) {
+        let from = self.compress(from).expect("line starts out of bounds");
+        let to = self.compress(to).expect("line ends out of bounds");
+        for cell in from.line_to(to) {
+            self.grid[cell] = value.clone();
+        }
+    }
+
+    /// The total real area of every cell for which `f` returns true
+    pub fn area_where<F: Fn(&V) -> bool>(&self, f: F) -> i64 {
+        self.grid
+            .iter()
+            .filter(|(_, value)| f(value))
+            .map(|(cell, _)| self.cell_area(cell))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompressedAxis, CompressedGrid};
+    use crate::Point;
+
+    #[test]
+    fn test_axis() {
+        let axis = CompressedAxis::new([10, 0, 1_000_000, 10]);
+        // margin, 0, 1..10, 10, 11..1_000_000, 1_000_000, margin
+        assert_eq!(axis.len(), 7);
+        assert_eq!(axis.index_of(-1), Some(0));
+        assert_eq!(axis.index_of(-2), None);
+        assert_eq!(axis.index_of(0), Some(1));
+        assert_eq!(axis.index_of(5), Some(2));
+        assert_eq!(axis.index_of(10), Some(3));
+        assert_eq!(axis.index_of(1_000_000), Some(5));
+        assert_eq!(axis.index_of(1_000_001), Some(6));
+        assert_eq!(axis.index_of(1_000_002), None);
+        assert_eq!(axis.start(2), 1);
+        assert_eq!(axis.size(2), 9);
+        assert_eq!(axis.size(4), 999_989);
+        assert_eq!(
+            (0..axis.len()).map(|i| axis.size(i)).sum::<i64>(),
+            1_000_003
+        );
+    }
+
+    fn lagoon(corners: &[Point]) -> i64 {
+        let mut g = CompressedGrid::new(corners.iter().cloned(), false);
+        for (from, to) in corners.iter().zip(corners.iter().cycle().skip(1)) {
+            g.fill_line(*from, *to, true);
+        }
+        let outside = g.grid.distance_field([Point::new(0, 0)], |wall| !wall);
+        let total = g.area_where(|_| true);
+        let exterior = outside
+            .iter()
+            .filter(|(_, distance)| distance.is_some())
+            .map(|(cell, _)| g.cell_area(cell))
+            .sum::<i64>();
+        total - exterior
+    }
+
+    #[test]
+    fn test_lagoon() {
+        let square = [
+            Point::new(0, 0),
+            Point::new(4_000_000, 0),
+            Point::new(4_000_000, 2),
+            Point::new(0, 2),
+        ];
+        assert_eq!(lagoon(&square), 4_000_001 * 3);
+
+        let l_shape = [
+            Point::new(0, 0),
+            Point::new(6, 0),
+            Point::new(6, 5),
+            Point::new(4, 5),
+            Point::new(4, 9),
+            Point::new(0, 9),
+        ];
+        assert_eq!(lagoon(&l_shape), 7 * 6 + 5 * 4);
+    }
+
+    #[test]
+    fn test_cells() {
+        let g = CompressedGrid::new([Point::new(-5, 100), Point::new(5, 300)], 0u8);
+        assert_eq!(g.compress(Point::new(0, 200)), Some(Point::new(2, 2)));
+        assert_eq!(g.real_origin(Point::new(2, 2)), Point::new(-4, 101));
+        assert_eq!(g.cell_size(Point::new(2, 2)), Point::new(9, 199));
+        assert_eq!(g.cell_area(Point::new(1, 1)), 1);
+        assert_eq!(g.compress(Point::new(100, 0)), None);
+    }
+}
@@ -0,0 +1,188 @@
+use num_traits::PrimInt;
+
+use super::dimval::DimVal;
+use super::grid::DenseGrid;
+use super::rect::Rect;
+use super::vec3::Vec3;
+
+/// An axis-aligned box. Both corners are inclusive, like [`Rect`], so a cuboid
+/// whose corners are the same point is a single unit cube. z points up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid<I: DimVal = i64> {
+    pub min: Vec3<I>,
+    pub max: Vec3<I>,
+}
+
+impl<I: DimVal> Cuboid<I> {
+    /// Build the cuboid spanning two opposite corners, given in any order
+    pub fn new(corner: Vec3<I>, opposite_corner: Vec3<I>) -> Self {
+        Cuboid {
+            min: corner.componentwise_min(opposite_corner),
+            max: corner.componentwise_max(opposite_corner),
+        }
+    }
+
+    pub fn contains(&self, p: Vec3<I>) -> bool {
+        self.min.componentwise_max(p) == p && self.max.componentwise_min(p) == p
+    }
+
+    /// The overlap between two cuboids, or None if they do not touch
+    pub fn intersection(&self, other: &Cuboid<I>) -> Option<Self> {
+        let min = self.min.componentwise_max(other.min);
+        let max = self.max.componentwise_min(other.max);
+        if min.componentwise_min(max) == min {
+            Some(Cuboid { min, max })
+        } else {
+            None
+        }
+    }
+
+    pub fn intersects(&self, other: &Cuboid<I>) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The smallest cuboid containing both cuboids
+    pub fn union(&self, other: &Cuboid<I>) -> Self {
+        Cuboid {
+            min: self.min.componentwise_min(other.min),
+            max: self.max.componentwise_max(other.max),
+        }
+    }
+
+    /// The shadow this cuboid casts straight down onto the XY plane
+    pub fn footprint(&self) -> Rect<I> {
+        Rect {
+            min: self.min.xy(),
+            max: self.max.xy(),
+        }
+    }
+
+    /// Whether the shadows of the two cuboids overlap, so that one would hit
+    /// the other if it fell straight down
+    pub fn footprint_overlaps(&self, other: &Cuboid<I>) -> bool {
+        self.footprint().intersection(&other.footprint()).is_some()
+    }
+
+    pub fn translate(&self, offset: Vec3<I>) -> Self {
+        Cuboid {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+
+    /// The same cuboid moved vertically so that its bottom is at `z`
+    pub fn with_bottom_at(&self, z: I) -> Self {
+        self.translate(Vec3::new(I::zero(), I::zero(), z - self.min.z))
+    }
+
+    /// Whether this cuboid sits directly on top of `other`, touching it
+    pub fn rests_on(&self, other: &Cuboid<I>) -> bool {
+        self.min.z - other.max.z == I::one() && self.footprint_overlaps(other)
+    }
+}
+
+impl<I: DimVal + PrimInt> Cuboid<I> {
+    /// The number of unit cubes along x, y and z
+    pub fn size(&self) -> (usize, usize, usize) {
+        let extent = |min: I, max: I| (max - min).to_usize().unwrap() + 1;
+        (
+            extent(self.min.x, self.max.x),
+            extent(self.min.y, self.max.y),
+            extent(self.min.z, self.max.z),
+        )
+    }
+
+    /// The number of unit cubes covered
+    pub fn volume(&self) -> usize {
+        let (x, y, z) = self.size();
+        x * y * z
+    }
+
+    /// Where this cuboid comes to rest if it falls straight down onto a stack
+    /// whose `heights` give the z of the highest filled cube in each column. The
+    /// cuboid moves so that its bottom is one above the tallest column under it,
+    /// so cuboids need to be dropped in order from the bottom up.
+    ///
+    /// Panics if the footprint is not inside `heights`.
+    pub fn drop_onto(&self, heights: &DenseGrid<I, I>) -> Self {
+        let top = self.footprint().points().map(|p| heights[p]).max().unwrap();
+        self.with_bottom_at(top + I::one())
+    }
+
+    /// Like [`Cuboid::drop_onto`], and then raise `heights` to the top of where
+    /// the cuboid landed
+    pub fn settle_onto(&self, heights: &mut DenseGrid<I, I>) -> Self {
+        let landed = self.drop_onto(heights);
+        for p in landed.footprint().points() {
+            heights[p] = landed.max.z;
+        }
+        landed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cuboid;
+    use crate::{DenseGrid, Point, Rect, Vec3};
+
+    #[test]
+    fn test_basic() {
+        let c = Cuboid::new(Vec3::new(2, 0, 5), Vec3::new(0, 1, 5));
+        assert_eq!(c.min, Vec3::new(0, 0, 5));
+        assert_eq!(c.max, Vec3::new(2, 1, 5));
+        assert_eq!(c.size(), (3, 2, 1));
+        assert_eq!(c.volume(), 6);
+        assert!(c.contains(Vec3::new(1, 1, 5)));
+        assert!(!c.contains(Vec3::new(1, 1, 6)));
+        assert_eq!(c.footprint(), Rect::new(Point::new(0, 0), Point::new(2, 1)));
+        let moved = c.translate(Vec3::new(1, 1, -4));
+        assert_eq!(moved, Cuboid::new(Vec3::new(1, 1, 1), Vec3::new(3, 2, 1)));
+        assert_eq!(c.with_bottom_at(1), c.translate(Vec3::new(0, 0, -4)));
+    }
+
+    #[test]
+    fn test_overlap() {
+        let a = Cuboid::new(Vec3::new(0, 0, 0), Vec3::new(3, 3, 3));
+        let b = Cuboid::new(Vec3::new(2, 1, 3), Vec3::new(5, 5, 5));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Cuboid::new(Vec3::new(2, 1, 3), Vec3::new(3, 3, 3)))
+        );
+        assert_eq!(
+            a.union(&b),
+            Cuboid::new(Vec3::new(0, 0, 0), Vec3::new(5, 5, 5))
+        );
+        let above = b.translate(Vec3::new(0, 0, 1));
+        assert!(!a.intersects(&above));
+        assert!(a.footprint_overlaps(&above));
+        assert!(above.rests_on(&a));
+        assert!(!a.rests_on(&above));
+        let beside = a.translate(Vec3::new(4, 0, 0));
+        assert!(!a.footprint_overlaps(&beside));
+    }
+
+    #[test]
+    fn test_settle() {
+        // the first three bricks of the 2023 day 22 example, and a tall one
+        // which lands across two of them
+        let bricks = [
+            Cuboid::new(Vec3::new(1, 0, 1), Vec3::new(1, 2, 1)),
+            Cuboid::new(Vec3::new(0, 0, 2), Vec3::new(2, 0, 2)),
+            Cuboid::new(Vec3::new(0, 2, 3), Vec3::new(2, 2, 3)),
+            Cuboid::new(Vec3::new(0, 0, 9), Vec3::new(0, 2, 10)),
+        ];
+        let mut heights = DenseGrid::new_with(Point::new(0, 0), Point::new(2, 2), 0);
+        let landed = bricks
+            .iter()
+            .map(|b| b.settle_onto(&mut heights))
+            .collect::<Vec<_>>();
+        assert_eq!(landed[0], bricks[0]);
+        assert_eq!(landed[1], bricks[1]);
+        assert_eq!(landed[2], bricks[2].with_bottom_at(2));
+        assert_eq!(landed[3], bricks[3].with_bottom_at(3));
+        assert_eq!(heights[Point::new(0, 1)], 4);
+        assert_eq!(heights[Point::new(1, 1)], 1);
+        assert!(landed[3].rests_on(&landed[1]));
+        assert!(landed[3].rests_on(&landed[2]));
+    }
+}
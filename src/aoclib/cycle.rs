@@ -0,0 +1,165 @@
+//! Finding where a repeated process starts going round in circles.
+//!
+//! Applying `step` over and over to an initial state gives the sequence
+//! `x0, x1 = step(x0), x2 = step(x1), ...`. If there are only finitely many
+//! states, that sequence eventually repeats: after `mu` steps it enters a loop,
+//! and from then on every state comes back around after `lambda` more steps.
+//! Once those are known, the state after any number of steps, however large, can
+//! be found by only stepping as far as its first appearance.
+//!
+//! Detection uses Brent's algorithm, which only ever holds two states at a time
+//! and only needs to compare them for equality. States which are expensive to
+//! compare can be compared through a cheaper key instead.
+
+/// The shape of a sequence which ends up in a loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// The index of the first state which is part of the loop
+    pub mu: usize,
+    /// The number of states in the loop
+    pub lambda: usize,
+}
+
+impl Cycle {
+    /// The smallest index whose state is the same as the state at `n`
+    pub fn first_index_of(&self, n: usize) -> usize {
+        if n < self.mu {
+            n
+        } else {
+            self.mu + (n - self.mu) % self.lambda
+        }
+    }
+
+    /// The state after `n` steps from `initial`, which takes at most
+    /// `mu + lambda` steps
+    pub fn state_at<T: Clone, F: FnMut(&T) -> T>(&self, initial: &T, mut step: F, n: usize) -> T {
+        (0..self.first_index_of(n)).fold(initial.clone(), |state, _| step(&state))
+    }
+}
+
+/// Find the loop in the sequence produced by applying `step` to `initial` over
+/// and over. Never returns if the sequence does not repeat.
+pub fn find_cycle<T: Clone + Eq, F: FnMut(&T) -> T>(initial: &T, step: F) -> Cycle {
+    find_cycle_by_key(initial, step, |state| state.clone())
+}
+
+/// Like [`find_cycle`], but comparing states by the result of `key`. States
+/// with the same key must go on to produce the same keys as each other, which
+/// holds whenever the key captures everything that decides the next state.
+/// [`Cycle::state_at`] then gives a state with the right key, but any other part
+/// of it is from the key's first appearance.
+pub fn find_cycle_by_key<T, K, F, G>(initial: &T, mut step: F, mut key: G) -> Cycle
+where
+    T: Clone,
+    K: Eq,
+    F: FnMut(&T) -> T,
+    G: FnMut(&T) -> K,
+{
+    // find lambda by sending the hare ahead in runs of doubling length, moving
+    // the tortoise up to meet it at the start of each run
+    let mut power = 1;
+    let mut lambda = 1;
+    let mut tortoise = key(initial);
+    let mut hare = step(initial);
+    let mut hare_key = key(&hare);
+    while tortoise != hare_key {
+        if power == lambda {
+            tortoise = hare_key;
+            power *= 2;
+            lambda = 0;
+        }
+        hare = step(&hare);
+        hare_key = key(&hare);
+        lambda += 1;
+    }
+
+    // then find mu by walking two states lambda apart until they meet
+    let mut tortoise = initial.clone();
+    let mut hare = (0..lambda).fold(initial.clone(), |state, _| step(&state));
+    let mut mu = 0;
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+    Cycle { mu, lambda }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_cycle, find_cycle_by_key, Cycle};
+    use std::collections::HashMap;
+
+    /// The same answer found by remembering every state
+    fn brute(initial: u64, step: impl Fn(&u64) -> u64) -> Cycle {
+        let mut seen = HashMap::new();
+        let mut state = initial;
+        for i in 0.. {
+            if let Some(first) = seen.insert(state, i) {
+                return Cycle {
+                    mu: first,
+                    lambda: i - first,
+                };
+            }
+            state = step(&state);
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for modulus in 1..60 {
+            for initial in [0, 3, 17] {
+                let step = |x: &u64| (x * x + 7) % modulus;
+                let initial = initial % modulus;
+                assert_eq!(
+                    find_cycle(&initial, step),
+                    brute(initial, step),
+                    "x -> x^2 + 7 mod {} from {}",
+                    modulus,
+                    initial
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_shapes() {
+        // a fixed point
+        assert_eq!(find_cycle(&5, |x| *x), Cycle { mu: 0, lambda: 1 });
+        // a pure loop
+        assert_eq!(
+            find_cycle(&0, |x| (x + 1) % 10),
+            Cycle { mu: 0, lambda: 10 }
+        );
+        // a long tail into a short loop
+        let cycle = find_cycle(&0, |x| if *x < 100 { x + 1 } else { 98 });
+        assert_eq!(cycle, Cycle { mu: 98, lambda: 3 });
+    }
+
+    #[test]
+    fn test_state_at() {
+        let step = |x: &u64| if *x < 100 { x + 1 } else { 98 };
+        let cycle = find_cycle(&0, step);
+        assert_eq!(cycle.first_index_of(50), 50);
+        assert_eq!(cycle.first_index_of(101), 98);
+        assert_eq!(cycle.state_at(&0, step, 50), 50);
+        assert_eq!(cycle.first_index_of(1_000_000_000_000), 100);
+        assert_eq!(cycle.state_at(&0, step, 1_000_000_000_000), 100);
+    }
+
+    #[test]
+    fn test_by_key() {
+        // the history makes every state distinct, but only the latest value
+        // decides what happens next
+        let step = |history: &Vec<u64>| {
+            let mut next = history.clone();
+            next.push((history.last().unwrap() * 3 + 1) % 23);
+            next
+        };
+        let cycle = find_cycle_by_key(&vec![2], step, |history| *history.last().unwrap());
+        assert_eq!(cycle, brute(2, |x| (x * 3 + 1) % 23));
+        let state = cycle.state_at(&vec![2], step, 1000);
+        assert_eq!(state.len(), cycle.first_index_of(1000) + 1);
+    }
+}
@@ -24,3 +24,86 @@ impl<
     > DimVal for S
 {
 }
+
+/// The smaller of two values which may only be partially ordered
+pub(crate) fn lesser<I: DimVal>(a: I, b: I) -> I {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+/// The larger of two values which may only be partially ordered
+pub(crate) fn greater<I: DimVal>(a: I, b: I) -> I {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+/// An integer [`DimVal`] with a wider type which can hold the product of any two
+/// of its values exactly. Methods which multiply coordinates together, like
+/// `dot` and `cross`, do their arithmetic in the wider type and are only
+/// available where one exists, so that there is no way to ask for the dot
+/// product of two `Point<i128>`s and silently get a wrapped answer.
+///
+/// For n-bit values, `MIN * MIN = 2^(2n-2)` is the largest magnitude a product
+/// can have, so the difference of two products needs at most 2n bits and fits
+/// in the wider type. A sum of two `MIN * MIN` products does not fit, because
+/// it is one more than the wider type's `MAX`. Methods which add products
+/// together check for that.
+///
+/// Floats have no wider type in which products are exact, so they have no
+/// implementation.
+pub trait Widen: DimVal + num_traits::PrimInt {
+    type Wide: DimVal + num_traits::PrimInt + From<Self>;
+}
+
+impl Widen for i8 {
+    type Wide = i16;
+}
+
+impl Widen for i16 {
+    type Wide = i32;
+}
+
+impl Widen for i32 {
+    type Wide = i64;
+}
+
+impl Widen for i64 {
+    type Wide = i128;
+}
+
+/// The distance between two whole numbers along one axis, or None if either
+/// has a fractional part. The subtraction is done in `i128`, so unlike
+/// subtracting them as they are, this cannot overflow, even for the extremes
+/// of `i128`.
+fn whole_distance<I: DimVal>(a: I, b: I) -> Option<u128> {
+    let whole = |v: I| v.to_f64().is_some_and(|f| f.fract() == 0.0);
+    if whole(a) && whole(b) {
+        Some(a.to_i128()?.abs_diff(b.to_i128()?))
+    } else {
+        None
+    }
+}
+
+/// The sum of the distances between two points along each axis, rounded down,
+/// or None if it does not fit in a usize. Axes with fractional coordinates are
+/// summed as they are and only rounded down at the end, and NaN or infinite
+/// values give None.
+pub(crate) fn manhattan_distance<I: DimVal, C: IntoIterator<Item = (I, I)>>(
+    components: C,
+) -> Option<usize> {
+    let mut whole = 0u128;
+    let mut fractional = I::zero();
+    for (a, b) in components {
+        match whole_distance(a, b) {
+            Some(distance) => whole = whole.checked_add(distance)?,
+            None => fractional = fractional + (a - b).abs(),
+        }
+    }
+    usize::try_from(whole.checked_add(fractional.to_u128()?)?).ok()
+}
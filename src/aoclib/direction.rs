@@ -0,0 +1,306 @@
+use super::dimval::DimVal;
+use super::point::{Point, Rotation};
+use std::fmt;
+
+/// One of the four orthogonal grid directions. As with the rest of the library,
+/// y grows downwards, so North is (0, -1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// Every direction, clockwise from North
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn opposite(self) -> Self {
+        self.turn_right().turn_right()
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        self.turn_right().opposite()
+    }
+
+    pub fn rotate_by(self, r: Rotation) -> Self {
+        match r {
+            Rotation::CW => self.turn_right(),
+            Rotation::CCW => self.turn_left(),
+            Rotation::Mirror => self.opposite(),
+        }
+    }
+
+    /// The unit vector pointing this way
+    pub fn as_point<I: DimVal>(self) -> Point<I> {
+        let zero = I::zero();
+        let one = I::one();
+        match self {
+            Direction::North => Point::new(zero, -one),
+            Direction::East => Point::new(one, zero),
+            Direction::South => Point::new(zero, one),
+            Direction::West => Point::new(-one, zero),
+        }
+    }
+
+    /// The direction of a unit vector, or None for anything else
+    pub fn from_point<I: DimVal>(p: Point<I>) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.as_point() == p)
+    }
+
+    /// Parse a single character in any of the common encodings: `U/R/D/L`,
+    /// `N/E/S/W` or `^/>/v/<`
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'U' | 'N' | '^' => Some(Direction::North),
+            'R' | 'E' | '>' => Some(Direction::East),
+            'D' | 'S' | 'v' => Some(Direction::South),
+            'L' | 'W' | '<' => Some(Direction::West),
+            _ => None,
+        }
+    }
+
+    /// Parse the digit encoding which counts clockwise starting from East, so `0`
+    /// is East, `1` is South, `2` is West and `3` is North
+    pub fn from_digit(c: char) -> Option<Self> {
+        match c {
+            '0' => Some(Direction::East),
+            '1' => Some(Direction::South),
+            '2' => Some(Direction::West),
+            '3' => Some(Direction::North),
+            _ => None,
+        }
+    }
+}
+
+impl<I: DimVal> From<Direction> for Point<I> {
+    fn from(d: Direction) -> Self {
+        d.as_point()
+    }
+}
+
+impl<I: DimVal> TryFrom<Point<I>> for Direction {
+    type Error = anyhow::Error;
+
+    fn try_from(p: Point<I>) -> Result<Self, Self::Error> {
+        Direction::from_point(p).ok_or_else(|| anyhow::anyhow!("{:?} is not a unit vector", p))
+    }
+}
+
+impl std::str::FromStr for Direction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars();
+        match (chars.next().and_then(Direction::from_char), chars.next()) {
+            (Some(d), None) => Ok(d),
+            _ => anyhow::bail!("unhandled direction {:?}", s),
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Direction::North => 'N',
+            Direction::East => 'E',
+            Direction::South => 'S',
+            Direction::West => 'W',
+        };
+        write!(f, "{}", c)
+    }
+}
+
+/// One of the eight compass directions, including diagonals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    /// Every direction, clockwise from North
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    fn step(self, eighths: usize) -> Self {
+        let index = Self::ALL.iter().position(|d| *d == self).unwrap();
+        Self::ALL[(index + eighths) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        self.step(4)
+    }
+
+    /// Turn 45 degrees clockwise
+    pub fn turn_right(self) -> Self {
+        self.step(1)
+    }
+
+    /// Turn 45 degrees counter-clockwise
+    pub fn turn_left(self) -> Self {
+        self.step(7)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        Direction::try_from(self).is_err()
+    }
+
+    /// The vector pointing this way; diagonals have both components set
+    pub fn as_point<I: DimVal>(self) -> Point<I> {
+        let zero = I::zero();
+        let one = I::one();
+        let (x, y) = match self {
+            Direction8::North => (zero, -one),
+            Direction8::NorthEast => (one, -one),
+            Direction8::East => (one, zero),
+            Direction8::SouthEast => (one, one),
+            Direction8::South => (zero, one),
+            Direction8::SouthWest => (-one, one),
+            Direction8::West => (-one, zero),
+            Direction8::NorthWest => (-one, -one),
+        };
+        Point::new(x, y)
+    }
+
+    pub fn from_point<I: DimVal>(p: Point<I>) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.as_point() == p)
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(d: Direction) -> Self {
+        match d {
+            Direction::North => Direction8::North,
+            Direction::East => Direction8::East,
+            Direction::South => Direction8::South,
+            Direction::West => Direction8::West,
+        }
+    }
+}
+
+impl TryFrom<Direction8> for Direction {
+    type Error = anyhow::Error;
+
+    fn try_from(d: Direction8) -> Result<Self, Self::Error> {
+        match d {
+            Direction8::North => Ok(Direction::North),
+            Direction8::East => Ok(Direction::East),
+            Direction8::South => Ok(Direction::South),
+            Direction8::West => Ok(Direction::West),
+            other => anyhow::bail!("{:?} is diagonal", other),
+        }
+    }
+}
+
+impl<I: DimVal> From<Direction8> for Point<I> {
+    fn from(d: Direction8) -> Self {
+        d.as_point()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Direction8};
+    use crate::{Point, Rotation};
+
+    #[test]
+    fn test_turns() {
+        for d in Direction::ALL {
+            assert_eq!(d.opposite().opposite(), d);
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.turn_left().turn_left(), d.opposite());
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::South.opposite(), Direction::North);
+    }
+
+    #[test]
+    fn test_points() {
+        for d in Direction::ALL {
+            assert_eq!(Direction::from_point(d.as_point::<i64>()), Some(d));
+            let p: Point<i32> = d.into();
+            assert_eq!(
+                d.rotate_by(Rotation::CW).as_point(),
+                p.rotate_by(Rotation::CW)
+            );
+            assert_eq!(
+                d.rotate_by(Rotation::CCW).as_point(),
+                p.rotate_by(Rotation::CCW)
+            );
+        }
+        assert_eq!(Direction::North.as_point(), Point::new(0, -1));
+        assert_eq!(Direction::from_point(Point::new(2, 0)), None);
+        assert!(Direction::try_from(Point::new(1, 1)).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        for (chars, expected) in [
+            ("UN^", Direction::North),
+            ("RE>", Direction::East),
+            ("DSv", Direction::South),
+            ("LW<", Direction::West),
+        ] {
+            for c in chars.chars() {
+                assert_eq!(Direction::from_char(c), Some(expected));
+            }
+        }
+        assert_eq!(Direction::from_char('x'), None);
+        assert_eq!(Direction::from_digit('0'), Some(Direction::East));
+        assert_eq!(Direction::from_digit('3'), Some(Direction::North));
+        assert_eq!(Direction::from_digit('4'), None);
+        assert_eq!("R".parse::<Direction>().unwrap(), Direction::East);
+        assert!("RR".parse::<Direction>().is_err());
+        assert!("".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_eight_way() {
+        assert_eq!(Direction8::North.turn_right(), Direction8::NorthEast);
+        assert_eq!(Direction8::North.turn_left(), Direction8::NorthWest);
+        assert_eq!(Direction8::SouthWest.opposite(), Direction8::NorthEast);
+        assert_eq!(Direction8::SouthEast.as_point(), Point::new(1, 1));
+        assert_eq!(
+            Direction8::from_point(Point::new(-1, -1)),
+            Some(Direction8::NorthWest)
+        );
+        assert!(Direction8::NorthEast.is_diagonal());
+        assert!(!Direction8::East.is_diagonal());
+        for d in Direction::ALL {
+            let d8 = Direction8::from(d);
+            assert_eq!(d8.as_point::<i64>(), d.as_point());
+            assert_eq!(Direction::try_from(d8).unwrap(), d);
+        }
+    }
+}
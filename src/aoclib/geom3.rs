@@ -0,0 +1,369 @@
+//! Lines and rays in three dimensions, with exact intersections.
+//!
+//! Origins and directions are integer [`Vec3`]s, but where two lines cross is
+//! usually a fraction of a step along each, so every answer is computed with
+//! [`BigRational`] and nothing is rounded. A line can equally be read as the path
+//! of something moving at a constant velocity, with the parameter as the time.
+
+use num::{BigInt, BigRational, Signed, ToPrimitive, Zero};
+
+use super::dimval::DimVal;
+use super::vec3::Vec3;
+
+/// How two lines relate to each other
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Intersection {
+    /// The lines meet at exactly one point, `t` steps along the first line and
+    /// `s` steps along the second
+    Crossing { t: BigRational, s: BigRational },
+    /// The lines point the same way but never meet
+    Parallel,
+    /// The lines are the same line, so they meet everywhere
+    Coincident,
+    /// The lines are not parallel but still never meet, which can only happen
+    /// in three dimensions
+    Skew,
+}
+
+/// When two moving points come closest together, and how close they get
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Approach {
+    pub time: BigRational,
+    /// The square of the distance, which unlike the distance itself is rational
+    pub distance_squared: BigRational,
+}
+
+impl Approach {
+    pub fn distance(&self) -> f64 {
+        self.distance_squared.to_f64().unwrap().sqrt()
+    }
+}
+
+type Exact = [BigInt; 3];
+
+fn lift<T: DimVal + Into<BigInt>>(v: Vec3<T>) -> Exact {
+    [v.x.into(), v.y.into(), v.z.into()]
+}
+
+fn sub(a: &Exact, b: &Exact) -> Exact {
+    [&a[0] - &b[0], &a[1] - &b[1], &a[2] - &b[2]]
+}
+
+fn dot(a: &Exact, b: &Exact) -> BigInt {
+    &a[0] * &b[0] + &a[1] * &b[1] + &a[2] * &b[2]
+}
+
+fn cross(a: &Exact, b: &Exact) -> Exact {
+    [
+        &a[1] * &b[2] - &a[2] * &b[1],
+        &a[2] * &b[0] - &a[0] * &b[2],
+        &a[0] * &b[1] - &a[1] * &b[0],
+    ]
+}
+
+/// The same vector with z set to zero
+fn flatten(a: &Exact) -> Exact {
+    [a[0].clone(), a[1].clone(), BigInt::zero()]
+}
+
+fn is_zero(a: &Exact) -> bool {
+    a.iter().all(Zero::is_zero)
+}
+
+/// Every point `origin + t * direction`, for any `t`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Line3<T: DimVal = i64> {
+    pub origin: Vec3<T>,
+    pub direction: Vec3<T>,
+}
+
+impl<T: DimVal + Into<BigInt>> Line3<T> {
+    /// Panics if `direction` is zero
+    pub fn new(origin: Vec3<T>, direction: Vec3<T>) -> Self {
+        assert!(
+            direction != Vec3::new(T::zero(), T::zero(), T::zero()),
+            "a line needs a direction"
+        );
+        Line3 { origin, direction }
+    }
+
+    /// The point `t` steps along the line
+    pub fn at(&self, t: &BigRational) -> [BigRational; 3] {
+        let [ox, oy, oz] = lift(self.origin);
+        let [dx, dy, dz] = lift(self.direction);
+        [
+            BigRational::from_integer(ox) + t * BigRational::from_integer(dx),
+            BigRational::from_integer(oy) + t * BigRational::from_integer(dy),
+            BigRational::from_integer(oz) + t * BigRational::from_integer(dz),
+        ]
+    }
+
+    fn intersect_exact(p: Exact, u: Exact, q: Exact, v: Exact) -> Intersection {
+        let w = sub(&q, &p);
+        let normal = cross(&u, &v);
+        if is_zero(&normal) {
+            return if is_zero(&cross(&w, &u)) {
+                Intersection::Coincident
+            } else {
+                Intersection::Parallel
+            };
+        }
+        if !dot(&w, &normal).is_zero() {
+            return Intersection::Skew;
+        }
+        let scale = dot(&normal, &normal);
+        Intersection::Crossing {
+            t: BigRational::new(dot(&cross(&w, &v), &normal), scale.clone()),
+            s: BigRational::new(dot(&cross(&w, &u), &normal), scale),
+        }
+    }
+
+    /// Where this line meets `other`
+    pub fn intersect(&self, other: &Line3<T>) -> Intersection {
+        Self::intersect_exact(
+            lift(self.origin),
+            lift(self.direction),
+            lift(other.origin),
+            lift(other.direction),
+        )
+    }
+
+    /// Where this line meets `other` when both are flattened onto the XY plane.
+    /// Lines which point straight along z flatten to a single point, and are
+    /// treated as parallel to everything.
+    pub fn intersect_xy(&self, other: &Line3<T>) -> Intersection {
+        let (u, v) = (
+            flatten(&lift(self.direction)),
+            flatten(&lift(other.direction)),
+        );
+        if is_zero(&u) || is_zero(&v) {
+            return Intersection::Parallel;
+        }
+        Self::intersect_exact(
+            flatten(&lift(self.origin)),
+            u,
+            flatten(&lift(other.origin)),
+            v,
+        )
+    }
+
+    /// The time at which two points moving along these lines, both starting at
+    /// their origin at time zero, are closest together. Points moving in
+    /// parallel at the same speed stay the same distance apart, and are reported
+    /// as closest at time zero.
+    pub fn closest_approach(&self, other: &Line3<T>) -> Approach {
+        let offset = sub(&lift(other.origin), &lift(self.origin));
+        let velocity = sub(&lift(other.direction), &lift(self.direction));
+        let speed_squared = dot(&velocity, &velocity);
+        let time = if speed_squared.is_zero() {
+            BigRational::zero()
+        } else {
+            BigRational::new(-dot(&offset, &velocity), speed_squared)
+        };
+        Approach {
+            distance_squared: separation_squared(&offset, &velocity, &time),
+            time,
+        }
+    }
+}
+
+/// The squared length of `offset + time * velocity`
+fn separation_squared(offset: &Exact, velocity: &Exact, time: &BigRational) -> BigRational {
+    offset
+        .iter()
+        .zip(velocity.iter())
+        .map(|(o, v)| {
+            let c =
+                BigRational::from_integer(o.clone()) + time * BigRational::from_integer(v.clone());
+            &c * &c
+        })
+        .fold(BigRational::zero(), |total, c| total + c)
+}
+
+/// Every point `origin + t * direction` for `t >= 0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ray3<T: DimVal = i64> {
+    pub origin: Vec3<T>,
+    pub direction: Vec3<T>,
+}
+
+impl<T: DimVal + Into<BigInt>> Ray3<T> {
+    /// Panics if `direction` is zero
+    pub fn new(origin: Vec3<T>, direction: Vec3<T>) -> Self {
+        Self::from(Line3::new(origin, direction))
+    }
+
+    /// The whole line which this ray is half of
+    pub fn line(&self) -> Line3<T> {
+        Line3 {
+            origin: self.origin,
+            direction: self.direction,
+        }
+    }
+
+    pub fn at(&self, t: &BigRational) -> [BigRational; 3] {
+        self.line().at(t)
+    }
+
+    /// A ray starts at its origin, so a crossing at `t` or `s` of exactly zero
+    /// still counts
+    fn ahead(intersection: Intersection) -> Option<(BigRational, BigRational)> {
+        match intersection {
+            Intersection::Crossing { t, s } if !t.is_negative() && !s.is_negative() => Some((t, s)),
+            _ => None,
+        }
+    }
+
+    /// The `t` and `s` at which the two rays cross, or None if they never do.
+    /// Either may be zero if the rays cross at an origin; callers which only want
+    /// crossings strictly ahead of both need to check for that. Use
+    /// [`Ray3::line`] to tell the ways in which they can miss apart.
+    pub fn crossing(&self, other: &Ray3<T>) -> Option<(BigRational, BigRational)> {
+        Self::ahead(self.line().intersect(&other.line()))
+    }
+
+    /// Like [`Ray3::crossing`], ignoring z
+    pub fn crossing_xy(&self, other: &Ray3<T>) -> Option<(BigRational, BigRational)> {
+        Self::ahead(self.line().intersect_xy(&other.line()))
+    }
+
+    /// Like [`Line3::closest_approach`], but never looking back before time zero
+    pub fn closest_approach(&self, other: &Ray3<T>) -> Approach {
+        let approach = self.line().closest_approach(&other.line());
+        if !approach.time.is_negative() {
+            return approach;
+        }
+        let offset = sub(&lift(other.origin), &lift(self.origin));
+        Approach {
+            time: BigRational::zero(),
+            distance_squared: BigRational::from_integer(dot(&offset, &offset)),
+        }
+    }
+}
+
+impl<T: DimVal> From<Line3<T>> for Ray3<T> {
+    fn from(line: Line3<T>) -> Self {
+        Ray3 {
+            origin: line.origin,
+            direction: line.direction,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Intersection, Line3, Ray3};
+    use crate::Vec3;
+    use num::BigRational;
+
+    fn q(n: i64, d: i64) -> BigRational {
+        BigRational::new(n.into(), d.into())
+    }
+
+    #[test]
+    fn test_crossing_xy() {
+        // the first two hailstones from the 2023 day 24 example
+        let a = Ray3::new(Vec3::new(19, 13, 30), Vec3::new(-2, 1, -2));
+        let b = Ray3::new(Vec3::new(18, 19, 22), Vec3::new(-1, -1, -2));
+        let (t, s) = a.crossing_xy(&b).unwrap();
+        let [x, y, _] = a.at(&t);
+        assert_eq!((x, y), (q(43, 3), q(46, 3)));
+        let [bx, by, _] = b.at(&s);
+        assert_eq!((bx, by), (q(43, 3), q(46, 3)));
+
+        // these two crossed in the past
+        let c = Ray3::new(Vec3::new(20, 19, 15), Vec3::new(1, -5, -3));
+        assert_eq!(a.crossing_xy(&c), None);
+        assert!(matches!(
+            a.line().intersect_xy(&c.line()),
+            Intersection::Crossing { .. }
+        ));
+    }
+
+    #[test]
+    fn test_crossing_at_origin() {
+        // b starts on a's path, and c starts where a does
+        let a = Ray3::new(Vec3::new(0, 0, 0), Vec3::new(1, 1, 0));
+        let b = Ray3::new(Vec3::new(2, 2, 5), Vec3::new(1, -1, 0));
+        let (zero, one) = (q(0, 1), q(1, 1));
+        assert_eq!(a.crossing_xy(&b), Some((q(2, 1), zero.clone())));
+        assert_eq!(a.crossing(&b), None);
+        let c = Ray3::new(Vec3::new(0, 0, 0), Vec3::new(-1, 2, 0));
+        assert_eq!(a.crossing(&c), Some((zero.clone(), zero.clone())));
+        // one step back along b is behind its origin
+        let d = Ray3::new(Vec3::new(3, 1, 0), Vec3::new(1, -1, 0));
+        assert_eq!(a.crossing_xy(&d), None);
+        assert_eq!(d.crossing_xy(&c), None);
+        let e = Ray3::new(Vec3::new(1, 1, 0), Vec3::new(0, 1, 0));
+        assert_eq!(a.crossing(&e), Some((one, zero)));
+    }
+
+    #[test]
+    fn test_parallel() {
+        let a = Line3::new(Vec3::new(0, 0, 0), Vec3::new(1, 2, 3));
+        let b = Line3::new(Vec3::new(1, 0, 0), Vec3::new(-2, -4, -6));
+        let c = Line3::new(Vec3::new(2, 4, 6), Vec3::new(2, 4, 6));
+        assert_eq!(a.intersect(&b), Intersection::Parallel);
+        assert_eq!(a.intersect(&c), Intersection::Coincident);
+        // moving z only changes anything in 3D
+        let d = Line3::new(Vec3::new(0, 0, 5), Vec3::new(1, 2, 0));
+        assert_eq!(a.intersect_xy(&d), Intersection::Coincident);
+        let up = Line3::new(Vec3::new(0, 0, 0), Vec3::new(0, 0, 1));
+        assert_eq!(a.intersect_xy(&up), Intersection::Parallel);
+    }
+
+    #[test]
+    fn test_crossing_3d() {
+        let a = Line3::new(Vec3::new(0, 0, 0), Vec3::new(2, 0, 0));
+        let b = Line3::new(Vec3::new(3, -1, 4), Vec3::new(0, 2, -8));
+        assert_eq!(
+            a.intersect(&b),
+            Intersection::Crossing {
+                t: q(3, 2),
+                s: q(1, 2)
+            }
+        );
+        let skew = Line3::new(Vec3::new(3, -1, 5), Vec3::new(0, 2, -8));
+        assert_eq!(a.intersect(&skew), Intersection::Skew);
+        assert!(matches!(
+            a.intersect_xy(&skew),
+            Intersection::Crossing { .. }
+        ));
+
+        let far = 400_000_000_000_000;
+        let huge = Line3::new(Vec3::new(far, far, far), Vec3::new(-3, -3, -3));
+        let axis = Line3::new(Vec3::new(0, 0, 0), Vec3::new(1, 1, 1));
+        assert_eq!(huge.intersect(&axis), Intersection::Coincident);
+        let across = Line3::new(Vec3::new(far, 0, far), Vec3::new(0, 7, 0));
+        assert_eq!(
+            axis.intersect(&across),
+            Intersection::Crossing {
+                t: q(far, 1),
+                s: q(far, 7)
+            }
+        );
+    }
+
+    #[test]
+    fn test_closest_approach() {
+        let a = Line3::new(Vec3::new(0, 0, 0), Vec3::new(1, 0, 0));
+        let b = Line3::new(Vec3::new(10, 3, 0), Vec3::new(-1, 0, 0));
+        let approach = a.closest_approach(&b);
+        assert_eq!(approach.time, q(5, 1));
+        assert_eq!(approach.distance_squared, q(9, 1));
+        assert_eq!(approach.distance(), 3.0);
+
+        assert_eq!(Ray3::from(a).closest_approach(&Ray3::from(b)), approach);
+
+        // moving apart, so a ray is closest right at the start
+        let behind = Ray3::new(Vec3::new(-10, 3, 0), Vec3::new(-1, 0, 0));
+        let approach = Ray3::from(a).closest_approach(&behind);
+        assert_eq!(approach.time, q(0, 1));
+        assert_eq!(approach.distance_squared, q(109, 1));
+
+        let together = Line3::new(Vec3::new(0, 4, 0), Vec3::new(1, 0, 0));
+        let approach = a.closest_approach(&together);
+        assert_eq!(approach.time, q(0, 1));
+        assert_eq!(approach.distance_squared, q(16, 1));
+    }
+}
@@ -1,9 +1,33 @@
-use std::cmp::{max, min};
+use std::cmp::{max, min, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+use std::convert::Infallible;
 use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
 
-use super::point::Point;
+use num::iter::range_inclusive;
+use num_traits::PrimInt;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-type Index = i64;
+use super::dimval::DimVal;
+use super::point::{Point, Transform};
+use super::rect::Rect;
+
+fn orthogonal<I: DimVal>() -> [Point<I>; 4] {
+    let zero = I::zero();
+    let one = I::one();
+    [
+        Point::new(zero, -one),
+        Point::new(one, zero),
+        Point::new(zero, one),
+        Point::new(-one, zero),
+    ]
+}
+
+/// The number of steps from `from` to `to`, which must not be less than `from`
+fn span<I: DimVal>(from: I, to: I) -> usize {
+    (to.to_i128().unwrap() - from.to_i128().unwrap()) as usize
+}
 
 pub trait HasEmpty {
     fn empty_value() -> Self;
@@ -15,21 +39,76 @@ impl<T: Default> HasEmpty for T {
     }
 }
 
+/// How to treat rows which are shorter or longer than the first row when parsing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ragged {
+    /// Fail with [`ParseGridError::Ragged`]
+    Reject,
+    /// Pad every row to the length of the longest one with the empty value
+    Pad,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGridError<E> {
+    /// The input did not contain any cells
+    Empty,
+    /// A row had a different length than the first row
+    Ragged {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The cell conversion function failed
+    InvalidCell {
+        line: usize,
+        column: usize,
+        source: E,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for ParseGridError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGridError::Empty => write!(f, "empty grid"),
+            ParseGridError::Ragged {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {} has {} cells, expected {}",
+                line, found, expected
+            ),
+            ParseGridError::InvalidCell {
+                line,
+                column,
+                source,
+            } => write!(
+                f,
+                "invalid cell at line {} column {}: {}",
+                line, column, source
+            ),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for ParseGridError<E> {}
+
 #[derive(Debug, Clone)]
-pub struct DenseGrid<V: Clone + fmt::Debug> {
-    pub min_x: Index,
-    pub min_y: Index,
-    pub max_x: Index,
-    pub max_y: Index,
+pub struct DenseGrid<V: Clone + fmt::Debug, I: DimVal = i64> {
+    pub min_x: I,
+    pub min_y: I,
+    pub max_x: I,
+    pub max_y: I,
     width: usize,
     height: usize,
     cells: Vec<V>,
 }
 
-impl<V: Clone + fmt::Debug + std::hash::Hash> std::hash::Hash for DenseGrid<V> {
+impl<V: Clone + fmt::Debug + Hash, I: DimVal + Hash> Hash for DenseGrid<V, I> {
     fn hash<H>(&self, state: &mut H)
     where
-        H: std::hash::Hasher,
+        H: Hasher,
     {
         self.min_x.hash(state);
         self.min_y.hash(state);
@@ -39,7 +118,7 @@ impl<V: Clone + fmt::Debug + std::hash::Hash> std::hash::Hash for DenseGrid<V> {
     }
 }
 
-impl<V: Clone + fmt::Debug + PartialEq> PartialEq for DenseGrid<V> {
+impl<V: Clone + fmt::Debug + PartialEq, I: DimVal> PartialEq for DenseGrid<V, I> {
     fn eq(&self, other: &Self) -> bool {
         self.min_x == other.min_x
             && self.min_y == other.min_y
@@ -49,43 +128,269 @@ impl<V: Clone + fmt::Debug + PartialEq> PartialEq for DenseGrid<V> {
     }
 }
 
-impl<V: Clone + fmt::Debug + PartialEq + Eq> Eq for DenseGrid<V> {}
+impl<V: Clone + fmt::Debug + PartialEq + Eq, I: DimVal + Eq> Eq for DenseGrid<V, I> {}
 
-impl<V: Clone + fmt::Debug + HasEmpty> DenseGrid<V> {
-    pub fn new(upper_left: Point<Index>, lower_right: Point<Index>) -> Self {
+/// A 128-bit hash of a grid's bounds and contents. Two grids with different
+/// fingerprints are definitely different; equal fingerprints are overwhelmingly
+/// likely (but not guaranteed) to mean equal grids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Fingerprint(pub u128);
+
+impl Fingerprint {
+    fn of<T: Hash + ?Sized>(value: &T) -> Self {
+        let mut low = DefaultHasher::new();
+        let mut high = DefaultHasher::new();
+        high.write_u64(0x9e37_79b9_7f4a_7c15);
+        value.hash(&mut low);
+        value.hash(&mut high);
+        Fingerprint(((high.finish() as u128) << 64) | low.finish() as u128)
+    }
+}
+
+/// A compact copy of a grid's state, storing one bit per cell. Hashing is O(1)
+/// and equality only compares the bits when the fingerprints match, so these are
+/// much cheaper than whole grids as `HashMap` keys. Snapshots of grids with
+/// different shapes are never equal, even if no cells are set.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    fingerprint: Fingerprint,
+    width: usize,
+    height: usize,
+    bits: Box<[u64]>,
+}
+
+impl Snapshot {
+    pub fn fingerprint(&self) -> Fingerprint {
+        self.fingerprint
+    }
+
+    /// The number of cells which were set when the snapshot was taken
+    pub fn count(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+}
+
+impl PartialEq for Snapshot {
+    fn eq(&self, other: &Self) -> bool {
+        self.fingerprint == other.fingerprint
+            && self.width == other.width
+            && self.height == other.height
+            && self.bits == other.bits
+    }
+}
+
+impl Eq for Snapshot {}
+
+impl Hash for Snapshot {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.fingerprint.hash(state);
+    }
+}
+
+/// One cell which differs between two grids. `old` or `new` is None if the
+/// point is out of bounds for that grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change<V, I: DimVal = i64> {
+    pub point: Point<I>,
+    pub old: Option<V>,
+    pub new: Option<V>,
+}
+
+impl<V: Clone + fmt::Debug + PartialEq, I: DimVal + PrimInt> DenseGrid<V, I> {
+    /// Every point within the bounds of either grid whose value differs, in
+    /// row-major order
+    pub fn diff(&self, other: &Self) -> Vec<Change<V, I>> {
+        let mut changes = vec![];
+        let (min_x, max_x) = (min(self.min_x, other.min_x), max(self.max_x, other.max_x));
+        for y in range_inclusive(min(self.min_y, other.min_y), max(self.max_y, other.max_y)) {
+            for x in range_inclusive(min_x, max_x) {
+                let point = Point::new(x, y);
+                let old = self.get(point);
+                let new = other.get(point);
+                if old != new {
+                    changes.push(Change { point, old, new });
+                }
+            }
+        }
+        changes
+    }
+
+    /// Render `self` and `other` side by side, followed by a third panel with a `*`
+    /// under every cell that differs. Intended for assertion messages, e.g.
+    /// `assert!(a == b, "{}", a.render_diff(&b, |c| *c))`.
+    pub fn render_diff<F: Fn(&V) -> char>(&self, other: &Self, f: F) -> String {
+        let min_x = min(self.min_x, other.min_x);
+        let max_x = max(self.max_x, other.max_x);
+        let min_y = min(self.min_y, other.min_y);
+        let max_y = max(self.max_y, other.max_y);
+        let width = max(span(min_x, max_x) + 1, "right".len());
+        let label_width = max(min_y.to_string().len(), max_y.to_string().len());
+        let panel = |g: &Self, y: I| {
+            range_inclusive(min_x, max_x)
+                .map(|x| g.get(Point::new(x, y)).map_or(' ', |v| f(&v)))
+                .collect::<String>()
+        };
+        let mut out = format!(
+            "{:label_width$}  {:width$}  {:width$}  {}\n",
+            "", "left", "right", "diff"
+        );
+        for y in range_inclusive(min_y, max_y) {
+            let markers = range_inclusive(min_x, max_x)
+                .map(|x| {
+                    let point = Point::new(x, y);
+                    if self.get(point) == other.get(point) {
+                        ' '
+                    } else {
+                        '*'
+                    }
+                })
+                .collect::<String>();
+            let line = format!(
+                "{:>label_width$}  {:width$}  {:width$}  {}",
+                y,
+                panel(self, y),
+                panel(other, y),
+                markers
+            );
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl<V: Clone + fmt::Debug + Hash, I: DimVal + Hash> DenseGrid<V, I> {
+    /// Hash the whole grid into a [`Fingerprint`]
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::of(self)
+    }
+}
+
+impl<V: Clone + fmt::Debug + HasEmpty, I: DimVal + PrimInt> DenseGrid<V, I> {
+    pub fn new(upper_left: Point<I>, lower_right: Point<I>) -> Self {
         Self::new_with(upper_left, lower_right, V::empty_value())
     }
+}
 
+// Parsing from text always produces the default index type; use
+// `DenseGrid::cast_index` to convert to another one.
+impl<V: Clone + fmt::Debug + HasEmpty> DenseGrid<V> {
+    /// Build a grid from a block of text, one row per line. Panics if the input is
+    /// empty; short rows are padded with the empty value.
     pub fn from_input<F>(input: &str, f: F) -> Self
     where
         F: Fn(char) -> V,
     {
-        let height = input.lines().count() as i64 - 1;
-        let width = input.lines().next().unwrap().chars().count() as i64 - 1;
-        let mut g = Self::new_with(
-            Point::new(0, 0),
-            Point::new(width, height),
-            V::empty_value(),
-        );
-        for (y, row) in input.lines().enumerate() {
-            for (x, chr) in row.chars().enumerate() {
-                let coord = Point::new(x as i64, y as i64);
-                let value = f(chr);
-                g.set(coord, value);
+        Self::try_from_input(input, Ragged::Pad, |c| Ok::<V, Infallible>(f(c)))
+            .unwrap_or_else(|e| panic!("invalid grid: {}", e))
+    }
+
+    /// Build a grid from a block of text, one row per line, with a fallible cell
+    /// conversion. Errors report the (1-based) line and column that failed.
+    pub fn try_from_input<F, E>(
+        input: &str,
+        ragged: Ragged,
+        f: F,
+    ) -> Result<Self, ParseGridError<E>>
+    where
+        F: Fn(char) -> Result<V, E>,
+    {
+        Self::from_lines(input.lines().enumerate(), ragged, &f)
+    }
+
+    /// Parse a batch of grids separated by blank lines. Line numbers in errors are
+    /// relative to the whole input.
+    pub fn parse_many<F, E>(
+        input: &str,
+        ragged: Ragged,
+        f: F,
+    ) -> Result<Vec<Self>, ParseGridError<E>>
+    where
+        F: Fn(char) -> Result<V, E>,
+    {
+        let mut grids = vec![];
+        let mut lines = input.lines().enumerate().peekable();
+        while lines.peek().is_some() {
+            let block = lines
+                .by_ref()
+                .skip_while(|(_, line)| line.trim().is_empty())
+                .take_while(|(_, line)| !line.trim().is_empty())
+                .collect::<Vec<_>>();
+            if !block.is_empty() {
+                grids.push(Self::from_lines(block.into_iter(), ragged, &f)?);
+            }
+        }
+        if grids.is_empty() {
+            return Err(ParseGridError::Empty);
+        }
+        Ok(grids)
+    }
+
+    fn from_lines<'a, L, F, E>(lines: L, ragged: Ragged, f: &F) -> Result<Self, ParseGridError<E>>
+    where
+        L: Iterator<Item = (usize, &'a str)>,
+        F: Fn(char) -> Result<V, E>,
+    {
+        let mut rows = vec![];
+        for (line_number, line) in lines {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(column, c)| {
+                    f(c).map_err(|source| ParseGridError::InvalidCell {
+                        line: line_number + 1,
+                        column: column + 1,
+                        source,
+                    })
+                })
+                .collect::<Result<Vec<V>, _>>()?;
+            rows.push((line_number + 1, row));
+        }
+        let width = match ragged {
+            Ragged::Reject => rows.first().map(|(_, row)| row.len()).unwrap_or(0),
+            Ragged::Pad => rows.iter().map(|(_, row)| row.len()).max().unwrap_or(0),
+        };
+        if width == 0 {
+            return Err(ParseGridError::Empty);
+        }
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (line, mut row) in rows {
+            if row.len() != width {
+                if ragged == Ragged::Reject {
+                    return Err(ParseGridError::Ragged {
+                        line,
+                        expected: width,
+                        found: row.len(),
+                    });
+                }
+                row.resize(width, V::empty_value());
             }
+            cells.extend(row);
         }
-        g
+        Ok(Self {
+            min_x: 0,
+            min_y: 0,
+            max_x: width as i64 - 1,
+            max_y: height as i64 - 1,
+            width,
+            height,
+            cells,
+        })
     }
 }
 
-impl<V: Clone + fmt::Debug> DenseGrid<V> {
-    pub fn new_with(upper_left: Point<Index>, lower_right: Point<Index>, empty_value: V) -> Self {
+impl<V: Clone + fmt::Debug, I: DimVal + PrimInt> DenseGrid<V, I> {
+    pub fn new_with(upper_left: Point<I>, lower_right: Point<I>, empty_value: V) -> Self {
         let min_x = min(upper_left.x, lower_right.x);
         let max_x = max(upper_left.x, lower_right.x);
         let min_y = min(upper_left.y, lower_right.y);
         let max_y = max(upper_left.y, lower_right.y);
-        let width = 1 + max_x.abs_diff(min_x) as usize;
-        let height = 1 + max_y.abs_diff(min_y) as usize;
+        let width = 1 + span(min_x, max_x);
+        let height = 1 + span(min_y, max_y);
         Self {
             min_x,
             max_x,
@@ -98,7 +403,7 @@ impl<V: Clone + fmt::Debug> DenseGrid<V> {
     }
 
     pub fn new_with_dimensions_from<T: Clone + fmt::Debug>(
-        g: &DenseGrid<T>,
+        g: &DenseGrid<T, I>,
         empty_value: V,
     ) -> Self {
         Self {
@@ -112,16 +417,35 @@ impl<V: Clone + fmt::Debug> DenseGrid<V> {
         }
     }
 
-    pub fn origin(&self) -> Point {
+    /// Convert the grid to use a different coordinate type. Returns None if the
+    /// bounds cannot be represented in the new type.
+    pub fn cast_index<J: DimVal + PrimInt>(self) -> Option<DenseGrid<V, J>> {
+        Some(DenseGrid {
+            min_x: J::from(self.min_x)?,
+            min_y: J::from(self.min_y)?,
+            max_x: J::from(self.max_x)?,
+            max_y: J::from(self.max_y)?,
+            width: self.width,
+            height: self.height,
+            cells: self.cells,
+        })
+    }
+
+    pub fn origin(&self) -> Point<I> {
         Point::new(self.min_x, self.min_y)
     }
 
-    pub fn row_numbers(&self) -> impl Iterator<Item = Index> {
-        self.min_y..=self.max_y
+    /// The rectangle covered by this grid
+    pub fn bounds(&self) -> Rect<I> {
+        Rect::new(self.origin(), Point::new(self.max_x, self.max_y))
+    }
+
+    pub fn row_numbers(&self) -> impl Iterator<Item = I> {
+        range_inclusive(self.min_y, self.max_y)
     }
 
-    pub fn column_numbers(&self) -> impl Iterator<Item = Index> {
-        self.min_x..=self.max_x
+    pub fn column_numbers(&self) -> impl Iterator<Item = I> {
+        range_inclusive(self.min_x, self.max_x)
     }
 
     pub fn width(&self) -> usize {
@@ -137,28 +461,26 @@ impl<V: Clone + fmt::Debug> DenseGrid<V> {
     }
 
     /// Get a value by coordinate. Returns None if the coordinate is out-of-bounds.
-    pub fn get(&self, coordinate: Point<Index>) -> Option<V> {
+    pub fn get(&self, coordinate: Point<I>) -> Option<V> {
         let index = self.index_for(coordinate)?;
         self.cells.get(index).cloned()
     }
 
     /// Set a value by coordinate. Returns None if the coordinate is out-of-bounds.
-    pub fn set(&mut self, coordinate: Point<Index>, value: V) -> Option<()> {
+    pub fn set(&mut self, coordinate: Point<I>, value: V) -> Option<()> {
         let index = self.index_for(coordinate)?;
         self.cells[index] = value;
         Some(())
     }
 
-    pub fn contains(&self, coordinate: Point<Index>) -> bool {
-        coordinate.x >= self.min_x
-            && coordinate.x <= self.max_x
-            && coordinate.y >= self.min_y
-            && coordinate.y <= self.max_y
+    pub fn contains(&self, coordinate: Point<I>) -> bool {
+        self.bounds().contains(coordinate)
     }
 
     pub fn dump_with<F: Fn(&V) -> char>(&self, f: F) {
-        for y in self.min_y..=self.max_y {
-            let cells = (self.min_x..=self.max_x)
+        for y in self.row_numbers() {
+            let cells = self
+                .column_numbers()
                 .map(|x| {
                     let coordinate = Point::new(x, y);
                     f(&self[coordinate])
@@ -180,13 +502,17 @@ impl<V: Clone + fmt::Debug> DenseGrid<V> {
         );
         for (point, value) in self.iter() {
             let point = point - self.origin();
-            image.put_pixel(point.x as u32, point.y as u32, f(&value));
+            image.put_pixel(
+                point.x.to_u32().unwrap(),
+                point.y.to_u32().unwrap(),
+                f(&value),
+            );
         }
         image.save(path.as_ref())?;
         Ok(())
     }
 
-    fn index_for(&self, coordinate: Point<Index>) -> Option<usize> {
+    fn index_for(&self, coordinate: Point<I>) -> Option<usize> {
         if coordinate.x < self.min_x
             || coordinate.x > self.max_x
             || coordinate.y < self.min_y
@@ -194,41 +520,235 @@ impl<V: Clone + fmt::Debug> DenseGrid<V> {
         {
             None
         } else {
-            let row = coordinate.y.abs_diff(self.min_y) as usize * self.width;
-            let col = coordinate.x.abs_diff(self.min_x) as usize;
+            let row = span(self.min_y, coordinate.y) * self.width;
+            let col = span(self.min_x, coordinate.x);
             Some(row + col)
         }
     }
 
-    pub fn rows(&self) -> Rows<V> {
+    fn point_for(&self, index: usize) -> Point<I> {
+        Point::new(
+            self.min_x + I::from(index % self.width).unwrap(),
+            self.min_y + I::from(index / self.width).unwrap(),
+        )
+    }
+
+    pub fn rows(&self) -> Rows<'_, V, I> {
         Rows {
             grid: self,
             y: self.min_y,
         }
     }
 
-    pub fn columns(&self) -> Columns<V> {
+    pub fn columns(&self) -> Columns<'_, V, I> {
         Columns {
             grid: self,
             x: self.min_x,
         }
     }
 
-    pub fn iter(&self) -> Iter<V> {
+    pub fn iter(&self) -> Iter<'_, V, I> {
         Iter {
             grid: self,
             x: self.min_x,
             y: self.min_y,
         }
     }
+
+    /// Build a new grid of the same shape by applying `f` to every cell
+    pub fn map<W, F>(&self, f: F) -> DenseGrid<W, I>
+    where
+        W: Clone + fmt::Debug,
+        F: Fn(&V) -> W,
+    {
+        DenseGrid {
+            min_x: self.min_x,
+            min_y: self.min_y,
+            max_x: self.max_x,
+            max_y: self.max_y,
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Rotate or reflect the whole grid. The result keeps the same upper-left
+    /// corner, so rotating by 90 degrees swaps the width and the height.
+    pub fn transform(&self, t: Transform) -> Self {
+        let origin = self.origin();
+        let far = t.apply(Point::new(self.max_x - self.min_x, self.max_y - self.min_y));
+        let shift = Point::new(min(I::zero(), far.x), min(I::zero(), far.y));
+        let mut out = DenseGrid::new_with(
+            origin,
+            origin + Point::new(far.x.abs(), far.y.abs()),
+            self.cells[0].clone(),
+        );
+        for (index, value) in self.cells.iter().enumerate() {
+            let point = t.apply(self.point_for(index) - origin) - shift + origin;
+            out[point] = value.clone();
+        }
+        out
+    }
+
+    /// Combine two grids with identical bounds cell by cell. Panics if the bounds
+    /// differ.
+    pub fn zip_with<U, W, F>(&self, other: &DenseGrid<U, I>, f: F) -> DenseGrid<W, I>
+    where
+        U: Clone + fmt::Debug,
+        W: Clone + fmt::Debug,
+        F: Fn(&V, &U) -> W,
+    {
+        assert!(
+            self.min_x == other.min_x
+                && self.min_y == other.min_y
+                && self.max_x == other.max_x
+                && self.max_y == other.max_y,
+            "cannot zip grids with different bounds"
+        );
+        DenseGrid {
+            min_x: self.min_x,
+            min_y: self.min_y,
+            max_x: self.max_x,
+            max_y: self.max_y,
+            width: self.width,
+            height: self.height,
+            cells: self
+                .cells
+                .iter()
+                .zip(other.cells.iter())
+                .map(|(a, b)| f(a, b))
+                .collect(),
+        }
+    }
+
+    /// Take a [`Snapshot`] of the cells for which `f` returns true
+    pub fn snapshot<F: Fn(&V) -> bool>(&self, f: F) -> Snapshot {
+        let mut bits = vec![0u64; self.cells.len().div_ceil(64)].into_boxed_slice();
+        for (i, value) in self.cells.iter().enumerate() {
+            if f(value) {
+                bits[i / 64] |= 1 << (i % 64);
+            }
+        }
+        Snapshot {
+            fingerprint: Fingerprint::of(&(self.width, self.height, &bits)),
+            width: self.width,
+            height: self.height,
+            bits,
+        }
+    }
+
+    /// Breadth-first search outwards from every point in `sources` at once, moving
+    /// orthogonally between cells for which `passable` returns true. Each cell of the
+    /// result holds the number of steps to the nearest source, or None if it cannot
+    /// be reached.
+    pub fn distance_field<S, F>(&self, sources: S, passable: F) -> DenseGrid<Option<usize>, I>
+    where
+        S: IntoIterator<Item = Point<I>>,
+        F: Fn(&V) -> bool,
+    {
+        let mut field = DenseGrid::new_with_dimensions_from(self, None);
+        let mut work = VecDeque::new();
+        for source in sources {
+            if field.set(source, Some(0)).is_some() {
+                work.push_back((source, 0));
+            }
+        }
+        while let Some((point, distance)) = work.pop_front() {
+            for direction in orthogonal() {
+                let next = point + direction;
+                match self.index_for(next) {
+                    Some(index) if field.cells[index].is_none() && passable(&self.cells[index]) => {
+                        field.cells[index] = Some(distance + 1);
+                        work.push_back((next, distance + 1));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        field
+    }
+
+    /// Like [`DenseGrid::distance_field`], but entering a cell costs whatever `cost`
+    /// returns for it (None means the cell cannot be entered).
+    pub fn weighted_distance_field<S, F>(&self, sources: S, cost: F) -> DenseGrid<Option<usize>, I>
+    where
+        S: IntoIterator<Item = Point<I>>,
+        F: Fn(Point<I>, &V) -> Option<usize>,
+    {
+        let mut field = DenseGrid::new_with_dimensions_from(self, None);
+        let mut work = BinaryHeap::new();
+        for source in sources {
+            if field.set(source, Some(0)).is_some() {
+                work.push((Reverse(0), source));
+            }
+        }
+        while let Some((Reverse(distance), point)) = work.pop() {
+            if field[point].is_some_and(|best| best < distance) {
+                continue;
+            }
+            for direction in orthogonal() {
+                let next = point + direction;
+                let Some(index) = self.index_for(next) else {
+                    continue;
+                };
+                let Some(step) = cost(next, &self.cells[index]) else {
+                    continue;
+                };
+                let candidate = distance + step;
+                if field.cells[index].is_none_or(|best| candidate < best) {
+                    field.cells[index] = Some(candidate);
+                    work.push((Reverse(candidate), next));
+                }
+            }
+        }
+        field
+    }
+
+    /// Walk from `from` in steps of `direction`, yielding every cell until the
+    /// walk leaves the grid. The starting cell itself is not included.
+    pub fn ray(&self, from: Point<I>, direction: Point<I>) -> Ray<'_, V, I> {
+        assert!(
+            direction != Point::new(I::zero(), I::zero()),
+            "cannot cast a ray with no direction"
+        );
+        Ray {
+            grid: self,
+            current: from,
+            direction,
+        }
+    }
+
+    /// Walk from `from` in steps of `direction` until `blocked` returns true.
+    ///
+    /// Returns the last cell which was not blocked (which is `from` if the very first
+    /// step is blocked) and the blocking cell, if the walk hit one before leaving the
+    /// grid.
+    pub fn cast_until<F>(
+        &self,
+        from: Point<I>,
+        direction: Point<I>,
+        blocked: F,
+    ) -> (Point<I>, Option<(Point<I>, &V)>)
+    where
+        F: Fn(&V) -> bool,
+    {
+        let mut last_free = from;
+        for (point, value) in self.ray(from, direction) {
+            if blocked(value) {
+                return (last_free, Some((point, value)));
+            }
+            last_free = point;
+        }
+        (last_free, None)
+    }
 }
 
-pub struct Rows<'a, V: Clone + std::fmt::Debug> {
-    grid: &'a DenseGrid<V>,
-    y: Index,
+pub struct Rows<'a, V: Clone + std::fmt::Debug, I: DimVal = i64> {
+    grid: &'a DenseGrid<V, I>,
+    y: I,
 }
 
-impl<'a, V: Clone + std::fmt::Debug> Iterator for Rows<'a, V> {
+impl<'a, V: Clone + std::fmt::Debug, I: DimVal + PrimInt> Iterator for Rows<'a, V, I> {
     type Item = Vec<V>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -243,17 +763,17 @@ impl<'a, V: Clone + std::fmt::Debug> Iterator for Rows<'a, V> {
                 self.grid.get(pt).unwrap()
             })
             .collect();
-        self.y += 1;
+        self.y = self.y + I::one();
         Some(val)
     }
 }
 
-pub struct Columns<'a, V: Clone + std::fmt::Debug> {
-    grid: &'a DenseGrid<V>,
-    x: Index,
+pub struct Columns<'a, V: Clone + std::fmt::Debug, I: DimVal = i64> {
+    grid: &'a DenseGrid<V, I>,
+    x: I,
 }
 
-impl<'a, V: Clone + std::fmt::Debug> Iterator for Columns<'a, V> {
+impl<'a, V: Clone + std::fmt::Debug, I: DimVal + PrimInt> Iterator for Columns<'a, V, I> {
     type Item = Vec<V>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -268,19 +788,19 @@ impl<'a, V: Clone + std::fmt::Debug> Iterator for Columns<'a, V> {
                 self.grid.get(pt).unwrap()
             })
             .collect();
-        self.x += 1;
+        self.x = self.x + I::one();
         Some(val)
     }
 }
 
-pub struct Iter<'a, V: Clone + std::fmt::Debug> {
-    grid: &'a DenseGrid<V>,
-    x: Index,
-    y: Index,
+pub struct Iter<'a, V: Clone + std::fmt::Debug, I: DimVal = i64> {
+    grid: &'a DenseGrid<V, I>,
+    x: I,
+    y: I,
 }
 
-impl<'a, V: Clone + std::fmt::Debug> Iterator for Iter<'a, V> {
-    type Item = (Point<Index>, V);
+impl<'a, V: Clone + std::fmt::Debug, I: DimVal + PrimInt> Iterator for Iter<'a, V, I> {
+    type Item = (Point<I>, V);
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.y > self.grid.max_y {
@@ -290,27 +810,145 @@ impl<'a, V: Clone + std::fmt::Debug> Iterator for Iter<'a, V> {
         let value = self.grid.get(pt).unwrap();
         if self.x >= self.grid.max_x {
             self.x = self.grid.min_x;
-            self.y += 1;
+            self.y = self.y + I::one();
         } else {
-            self.x += 1;
+            self.x = self.x + I::one();
         }
         Some((pt, value))
     }
 }
 
-impl<'a, V: Clone + std::fmt::Debug> std::iter::FusedIterator for Iter<'a, V> {}
+impl<'a, V: Clone + std::fmt::Debug, I: DimVal + PrimInt> std::iter::FusedIterator
+    for Iter<'a, V, I>
+{
+}
+
+pub struct Ray<'a, V: Clone + std::fmt::Debug, I: DimVal = i64> {
+    grid: &'a DenseGrid<V, I>,
+    current: Point<I>,
+    direction: Point<I>,
+}
+
+impl<'a, V: Clone + std::fmt::Debug, I: DimVal + PrimInt> Iterator for Ray<'a, V, I> {
+    type Item = (Point<I>, &'a V);
 
-impl<V: Clone + std::fmt::Debug> std::ops::Index<Point<Index>> for DenseGrid<V> {
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.current + self.direction;
+        let index = self.grid.index_for(next)?;
+        self.current = next;
+        Some((next, &self.grid.cells[index]))
+    }
+}
+
+impl<'a, V: Clone + std::fmt::Debug, I: DimVal + PrimInt> std::iter::FusedIterator
+    for Ray<'a, V, I>
+{
+}
+
+impl<V: Clone + fmt::Debug + HasEmpty, I: DimVal + PrimInt> Extend<(Point<I>, V)>
+    for DenseGrid<V, I>
+{
+    /// Set every point from the iterator, growing the grid (and filling the new
+    /// space with the empty value) if any of them are out of bounds
+    fn extend<T: IntoIterator<Item = (Point<I>, V)>>(&mut self, iter: T) {
+        let items = iter.into_iter().collect::<Vec<_>>();
+        let mut upper_left = self.origin();
+        let mut lower_right = Point::new(self.max_x, self.max_y);
+        for (point, _) in items.iter() {
+            upper_left = Point::new(min(upper_left.x, point.x), min(upper_left.y, point.y));
+            lower_right = Point::new(max(lower_right.x, point.x), max(lower_right.y, point.y));
+        }
+        if upper_left != self.origin() || lower_right != Point::new(self.max_x, self.max_y) {
+            let mut grown = Self::new(upper_left, lower_right);
+            for (index, value) in std::mem::take(&mut self.cells).into_iter().enumerate() {
+                grown[self.point_for(index)] = value;
+            }
+            *self = grown;
+        }
+        for (point, value) in items {
+            self[point] = value;
+        }
+    }
+}
+
+impl<V: Clone + fmt::Debug + HasEmpty, I: DimVal + PrimInt> DenseGrid<V, I> {
+    /// Build the smallest grid containing every point from the iterator; cells
+    /// which were not mentioned hold the empty value. Returns None if there are
+    /// no points, since a grid always covers at least one cell.
+    pub fn try_from_points<T: IntoIterator<Item = (Point<I>, V)>>(points: T) -> Option<Self> {
+        let mut points = points.into_iter();
+        let (first, value) = points.next()?;
+        let mut grid = Self::new_with(first, first, value);
+        grid.extend(points);
+        Some(grid)
+    }
+}
+
+impl<V: Clone + fmt::Debug + HasEmpty, I: DimVal + PrimInt> FromIterator<(Point<I>, V)>
+    for DenseGrid<V, I>
+{
+    /// Build the smallest grid containing every point from the iterator, as with
+    /// [`DenseGrid::try_from_points`]. Panics if the iterator is empty.
+    fn from_iter<T: IntoIterator<Item = (Point<I>, V)>>(iter: T) -> Self {
+        Self::try_from_points(iter).expect("cannot build a grid from an empty iterator")
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<V, I> DenseGrid<V, I>
+where
+    V: Clone + fmt::Debug + Sync,
+    I: DimVal + PrimInt + Send + Sync,
+{
+    /// Like [`DenseGrid::iter`], but spread across the rayon thread pool
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = (Point<I>, &V)> {
+        self.cells
+            .par_iter()
+            .enumerate()
+            .map(move |(index, value)| (self.point_for(index), value))
+    }
+
+    /// Every row of the grid, top to bottom, spread across the rayon thread pool
+    pub fn par_rows(&self) -> impl IndexedParallelIterator<Item = &[V]> {
+        self.cells.par_chunks(self.width)
+    }
+
+    /// Build a new grid of the same shape by evaluating `f` on every cell in parallel
+    pub fn par_map<W, F>(&self, f: F) -> DenseGrid<W, I>
+    where
+        W: Clone + fmt::Debug + Send,
+        F: Fn(Point<I>, &V) -> W + Sync + Send,
+    {
+        DenseGrid {
+            min_x: self.min_x,
+            min_y: self.min_y,
+            max_x: self.max_x,
+            max_y: self.max_y,
+            width: self.width,
+            height: self.height,
+            cells: self
+                .par_iter()
+                .map(|(point, value)| f(point, value))
+                .collect(),
+        }
+    }
+}
+
+impl<V: Clone + std::fmt::Debug, I: DimVal + PrimInt> std::ops::Index<Point<I>>
+    for DenseGrid<V, I>
+{
     type Output = V;
 
-    fn index(&self, coordinate: Point<Index>) -> &Self::Output {
+    fn index(&self, coordinate: Point<I>) -> &Self::Output {
         let index = self.index_for(coordinate).unwrap();
         self.cells.get(index).unwrap()
     }
 }
 
-impl<V: Clone + std::fmt::Debug> std::ops::IndexMut<Point<Index>> for DenseGrid<V> {
-    fn index_mut(&mut self, coordinate: Point<Index>) -> &mut Self::Output {
+impl<V: Clone + std::fmt::Debug, I: DimVal + PrimInt> std::ops::IndexMut<Point<I>>
+    for DenseGrid<V, I>
+{
+    fn index_mut(&mut self, coordinate: Point<I>) -> &mut Self::Output {
         let index = self.index_for(coordinate).unwrap();
         self.cells.get_mut(index).unwrap()
     }
@@ -318,7 +956,7 @@ impl<V: Clone + std::fmt::Debug> std::ops::IndexMut<Point<Index>> for DenseGrid<
 
 #[cfg(test)]
 mod tests {
-    use super::{DenseGrid, Point};
+    use super::{Change, DenseGrid, ParseGridError, Point, Ragged, Transform};
 
     #[test]
     fn test_small() {
@@ -358,4 +996,362 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_ray() {
+        let g = DenseGrid::from_input("abc\ndef\nghi", |c| c);
+        assert_eq!(
+            g.ray(Point::new(0, 0), Point::new(1, 1))
+                .map(|(_, c)| *c)
+                .collect::<String>(),
+            "ei"
+        );
+        assert_eq!(
+            g.ray(Point::new(2, 1), Point::new(-1, 0))
+                .collect::<Vec<_>>(),
+            vec![(Point::new(1, 1), &'e'), (Point::new(0, 1), &'d')]
+        );
+        assert_eq!(g.ray(Point::new(2, 2), Point::new(0, 1)).count(), 0);
+    }
+
+    #[test]
+    fn test_cast_until() {
+        let g = DenseGrid::from_input("..#.\n....", |c| c);
+        let (free, hit) = g.cast_until(Point::new(0, 0), Point::new(1, 0), |c| *c == '#');
+        assert_eq!(free, Point::new(1, 0));
+        assert_eq!(hit, Some((Point::new(2, 0), &'#')));
+        let (free, hit) = g.cast_until(Point::new(0, 1), Point::new(1, 0), |c| *c == '#');
+        assert_eq!(free, Point::new(3, 1));
+        assert_eq!(hit, None);
+        let (free, hit) = g.cast_until(Point::new(3, 0), Point::new(-1, 0), |c| *c == '#');
+        assert_eq!(free, Point::new(3, 0));
+        assert_eq!(hit, Some((Point::new(2, 0), &'#')));
+    }
+
+    fn parse_digit(c: char) -> Result<u8, String> {
+        c.to_digit(10)
+            .map(|d| d as u8)
+            .ok_or_else(|| format!("{:?} is not a digit", c))
+    }
+
+    #[test]
+    fn test_try_from_input() {
+        let g = DenseGrid::try_from_input("12\n34\n", Ragged::Reject, parse_digit).unwrap();
+        assert_eq!(g.width(), 2);
+        assert_eq!(g.height(), 2);
+        assert_eq!(g[Point::new(1, 1)], 4);
+
+        assert_eq!(
+            DenseGrid::try_from_input("12\n3x", Ragged::Reject, parse_digit),
+            Err(ParseGridError::InvalidCell {
+                line: 2,
+                column: 2,
+                source: "'x' is not a digit".to_string()
+            })
+        );
+        assert_eq!(
+            DenseGrid::try_from_input("", Ragged::Reject, parse_digit),
+            Err(ParseGridError::Empty)
+        );
+    }
+
+    #[test]
+    fn test_try_from_input_ragged() {
+        assert_eq!(
+            DenseGrid::try_from_input("12\n3\n45", Ragged::Reject, parse_digit),
+            Err(ParseGridError::Ragged {
+                line: 2,
+                expected: 2,
+                found: 1
+            })
+        );
+        let g = DenseGrid::try_from_input("1\n234", Ragged::Pad, parse_digit).unwrap();
+        assert_eq!(g.width(), 3);
+        assert_eq!(
+            g.rows().collect::<Vec<_>>(),
+            vec![vec![1, 0, 0], vec![2, 3, 4]]
+        );
+    }
+
+    #[test]
+    fn test_parse_many() {
+        let grids =
+            DenseGrid::parse_many("12\n34\n\n\n5\n6\n", Ragged::Reject, parse_digit).unwrap();
+        assert_eq!(grids.len(), 2);
+        assert_eq!(grids[1].columns().collect::<Vec<_>>(), vec![vec![5, 6]]);
+
+        assert_eq!(
+            DenseGrid::parse_many("12\n\n5a", Ragged::Reject, parse_digit),
+            Err(ParseGridError::InvalidCell {
+                line: 3,
+                column: 2,
+                source: "'a' is not a digit".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_distance_field() {
+        let g = DenseGrid::from_input("..#.\n.#..\n....", |c| c);
+        let field = g.distance_field([Point::new(0, 0)], |c| *c == '.');
+        assert_eq!(field[Point::new(0, 0)], Some(0));
+        assert_eq!(field[Point::new(1, 0)], Some(1));
+        assert_eq!(field[Point::new(2, 0)], None);
+        assert_eq!(field[Point::new(1, 1)], None);
+        assert_eq!(field[Point::new(3, 0)], Some(7));
+
+        let field = g.distance_field([Point::new(0, 0), Point::new(3, 0)], |c| *c == '.');
+        assert_eq!(field[Point::new(3, 1)], Some(1));
+        assert_eq!(field[Point::new(1, 2)], Some(3));
+        let exactly_two = field
+            .iter()
+            .filter(|(_, d)| d.is_some_and(|d| d <= 2 && d % 2 == 0))
+            .count();
+        assert_eq!(exactly_two, 5);
+    }
+
+    #[test]
+    fn test_weighted_distance_field() {
+        let g = DenseGrid::from_input("191\n111\n991", |c| c.to_digit(10).unwrap() as usize);
+        let field = g.weighted_distance_field([Point::new(0, 0)], |_, cost| Some(*cost));
+        assert_eq!(field[Point::new(2, 0)], Some(4));
+        assert_eq!(field[Point::new(2, 2)], Some(4));
+        assert_eq!(field[Point::new(0, 2)], Some(10));
+
+        let field = g.weighted_distance_field([Point::new(0, 0)], |_, cost| {
+            if *cost < 9 {
+                Some(*cost)
+            } else {
+                None
+            }
+        });
+        assert_eq!(field[Point::new(0, 2)], None);
+        assert_eq!(field[Point::new(1, 0)], None);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let a = DenseGrid::from_input("ab\ncd", |c| c);
+        let mut b = a.clone();
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        b[Point::new(1, 1)] = 'x';
+        assert_ne!(a.fingerprint(), b.fingerprint());
+        b[Point::new(1, 1)] = 'd';
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        let c = DenseGrid::from_input("abcd", |c| c);
+        assert_ne!(a.fingerprint(), c.fingerprint());
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let a = DenseGrid::from_input(&"#.".repeat(50), |c| c);
+        let mut b = a.clone();
+        let snapshot = a.snapshot(|c| *c == '#');
+        assert_eq!(snapshot.count(), 50);
+        assert_eq!(snapshot, b.snapshot(|c| *c == '#'));
+        b[Point::new(99, 0)] = '#';
+        let other = b.snapshot(|c| *c == '#');
+        assert_ne!(snapshot, other);
+        assert_ne!(snapshot.fingerprint(), other.fingerprint());
+        assert_eq!(other.count(), 51);
+
+        let mut seen = std::collections::HashMap::new();
+        seen.insert(snapshot.clone(), 0);
+        seen.insert(other, 1);
+        assert_eq!(seen.get(&a.snapshot(|c| *c == '#')), Some(&0));
+    }
+
+    #[test]
+    fn test_snapshot_shape() {
+        // with nothing set, only the shape tells these apart
+        let one = DenseGrid::from_input(".", |c| c).snapshot(|c| *c == '#');
+        let two = DenseGrid::from_input(".\n.", |c| c).snapshot(|c| *c == '#');
+        let wide = DenseGrid::from_input("..", |c| c).snapshot(|c| *c == '#');
+        assert_ne!(one, two);
+        assert_ne!(one.fingerprint(), two.fingerprint());
+        assert_ne!(two, wide);
+        assert_ne!(two.fingerprint(), wide.fingerprint());
+    }
+
+    #[test]
+    fn test_diff() {
+        let a = DenseGrid::from_input("ab\ncd", |c| c);
+        let mut b = a.clone();
+        assert_eq!(a.diff(&b), vec![]);
+        b[Point::new(0, 1)] = 'x';
+        assert_eq!(
+            a.diff(&b),
+            vec![Change {
+                point: Point::new(0, 1),
+                old: Some('c'),
+                new: Some('x')
+            }]
+        );
+
+        let c = DenseGrid::from_input("abe", |c| c);
+        let changes = a.diff(&c);
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].point, Point::new(2, 0));
+        assert_eq!(changes[0].old, None);
+        assert_eq!(changes[0].new, Some('e'));
+    }
+
+    #[test]
+    fn test_render_diff() {
+        let a = DenseGrid::from_input("..#\n...", |c| c);
+        let b = DenseGrid::from_input("..#\n.O.", |c| c);
+        assert_eq!(
+            a.render_diff(&b, |c| *c),
+            "   left   right  diff\n0  ..#    ..#\n1  ...    .O.     *\n"
+        );
+    }
+
+    #[test]
+    fn test_small_index() {
+        let g = DenseGrid::from_input("ab\ncd", |c| c)
+            .cast_index::<i16>()
+            .unwrap();
+        assert_eq!(g[Point::<i16>::new(1, 1)], 'd');
+        assert_eq!(g.get(Point::new(2, 0)), None);
+        assert!(g.contains(Point::new(0, 1)));
+        assert_eq!(
+            g.iter().map(|(p, _)| p).last(),
+            Some(Point::<i16>::new(1, 1))
+        );
+        assert_eq!(
+            g.ray(Point::new(0, 0), Point::new(1, 1)).next(),
+            Some((Point::new(1, 1), &'d'))
+        );
+
+        let mut big = DenseGrid::new_with(Point::<i64>::new(0, 0), Point::new(1, 200), 0u8);
+        big[Point::new(1, 200)] = 1;
+        assert!(big.clone().cast_index::<i8>().is_none());
+        let small = big.cast_index::<i32>().unwrap();
+        assert_eq!(small[Point::<i32>::new(1, 200)], 1);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel() {
+        use rayon::prelude::*;
+
+        let g = DenseGrid::from_input("123\n456", |c| c.to_digit(10).unwrap());
+        assert_eq!(
+            g.par_iter().map(|(p, v)| (p, *v)).collect::<Vec<_>>(),
+            g.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            g.par_rows()
+                .map(|r| r.iter().sum::<u32>())
+                .collect::<Vec<_>>(),
+            vec![6, 15]
+        );
+        let scores = g.par_map(|p, v| p.x * 10 + *v as i64);
+        assert_eq!(scores[Point::new(2, 1)], 26);
+        assert_eq!(scores.width(), 3);
+        assert_eq!(scores.height(), 2);
+    }
+
+    #[test]
+    fn test_map_and_zip() {
+        let heat = DenseGrid::from_input("123\n456", |c| c.to_digit(10).unwrap());
+        let hot = heat.map(|v| *v > 3);
+        assert_eq!(
+            hot.rows().collect::<Vec<_>>(),
+            vec![vec![false, false, false], vec![true, true, true]]
+        );
+        let path = DenseGrid::from_input("#..\n.##", |c| c == '#');
+        let overlay = heat.zip_with(&path, |h, on_path| if *on_path { 0 } else { *h });
+        assert_eq!(
+            overlay.rows().collect::<Vec<_>>(),
+            vec![vec![0, 2, 3], vec![4, 0, 0]]
+        );
+    }
+
+    #[test]
+    fn test_bounds() {
+        let g = DenseGrid::new_with(Point::new(4, -2), Point::new(-1, 3), 0u8);
+        let bounds = g.bounds();
+        assert_eq!(bounds.min, Point::new(-1, -2));
+        assert_eq!(bounds.max, Point::new(4, 3));
+        assert_eq!(bounds.area(), g.size());
+        assert!(bounds.points().all(|p| g.contains(p)));
+        assert_eq!(
+            bounds.points().collect::<Vec<_>>(),
+            g.iter().map(|(p, _)| p).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_transform() {
+        let g = DenseGrid::from_input("12\n34\n56", |c| c);
+        let rendered = |t: Transform| {
+            g.transform(t)
+                .rows()
+                .map(|row| row.into_iter().collect::<String>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rendered(Transform::Identity), vec!["12", "34", "56"]);
+        assert_eq!(rendered(Transform::RotateCW), vec!["531", "642"]);
+        assert_eq!(rendered(Transform::RotateCCW), vec!["246", "135"]);
+        assert_eq!(rendered(Transform::Rotate180), vec!["65", "43", "21"]);
+        assert_eq!(rendered(Transform::ReflectX), vec!["56", "34", "12"]);
+        assert_eq!(rendered(Transform::ReflectY), vec!["21", "43", "65"]);
+        assert_eq!(rendered(Transform::ReflectDiagonal), vec!["135", "246"]);
+        assert_eq!(rendered(Transform::ReflectAntiDiagonal), vec!["642", "531"]);
+
+        let offset = DenseGrid::new_with(Point::new(-3, 7), Point::new(1, 8), 0u8);
+        let rotated = offset.transform(Transform::RotateCW);
+        assert_eq!(rotated.origin(), Point::new(-3, 7));
+        assert_eq!((rotated.width(), rotated.height()), (2, 5));
+        for t in Transform::ALL {
+            assert_eq!(g.transform(t).transform(t.inverse()), g);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "different bounds")]
+    fn test_zip_mismatched() {
+        let a = DenseGrid::from_input("12", |c| c);
+        let b = DenseGrid::from_input("1\n2", |c| c);
+        a.zip_with(&b, |_, _| ());
+    }
+
+    #[test]
+    fn test_from_points_and_extend() {
+        let mut g = DenseGrid::try_from_points([(Point::new(2, 1), 'a'), (Point::new(-1, 0), 'b')])
+            .unwrap();
+        assert_eq!((g.min_x, g.min_y, g.max_x, g.max_y), (-1, 0, 2, 1));
+        assert_eq!(g[Point::new(2, 1)], 'a');
+        assert_eq!(g[Point::new(-1, 0)], 'b');
+        assert_eq!(g[Point::new(0, 0)], '\0');
+
+        g.extend([(Point::new(0, 0), 'c')]);
+        assert_eq!(g.size(), 8);
+        g.extend([(Point::new(0, 3), 'd')]);
+        assert_eq!((g.min_x, g.min_y, g.max_x, g.max_y), (-1, 0, 2, 3));
+        assert_eq!(g[Point::new(2, 1)], 'a');
+        assert_eq!(g[Point::new(-1, 0)], 'b');
+        assert_eq!(g[Point::new(0, 0)], 'c');
+        assert_eq!(g[Point::new(0, 3)], 'd');
+        assert_eq!(g.iter().filter(|(_, c)| *c != '\0').count(), 4);
+
+        let none = g.iter().filter(|(_, c)| *c == 'z');
+        assert_eq!(DenseGrid::<char>::try_from_points(none), None);
+
+        let collected = [(Point::new(2, 1), 'a'), (Point::new(-1, 0), 'b')]
+            .into_iter()
+            .collect::<DenseGrid<char>>();
+        assert_eq!(
+            collected,
+            DenseGrid::try_from_points([(Point::new(-1, 0), 'b'), (Point::new(2, 1), 'a')])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "empty iterator")]
+    fn test_collect_empty() {
+        let _ = std::iter::empty::<(Point, char)>().collect::<DenseGrid<char>>();
+    }
 }
@@ -0,0 +1,340 @@
+use std::cmp::{max, min};
+use std::fmt;
+
+use super::grid::{DenseGrid, HasEmpty};
+use super::point::Point;
+use super::vec3::Vec3;
+
+type Index = i64;
+
+const FACES: [(Index, Index, Index); 6] = [
+    (-1, 0, 0),
+    (1, 0, 0),
+    (0, -1, 0),
+    (0, 1, 0),
+    (0, 0, -1),
+    (0, 0, 1),
+];
+
+/// A dense 3D grid of voxels, stored one z-layer at a time
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DenseGrid3<V: Clone + fmt::Debug> {
+    pub min: Vec3<Index>,
+    pub max: Vec3<Index>,
+    width: usize,
+    height: usize,
+    depth: usize,
+    cells: Vec<V>,
+}
+
+impl<V: Clone + fmt::Debug + HasEmpty> DenseGrid3<V> {
+    pub fn new(corner: Vec3<Index>, opposite_corner: Vec3<Index>) -> Self {
+        Self::new_with(corner, opposite_corner, V::empty_value())
+    }
+}
+
+impl<V: Clone + fmt::Debug> DenseGrid3<V> {
+    pub fn new_with(corner: Vec3<Index>, opposite_corner: Vec3<Index>, empty_value: V) -> Self {
+        let min = Vec3::new(
+            min(corner.x, opposite_corner.x),
+            min(corner.y, opposite_corner.y),
+            min(corner.z, opposite_corner.z),
+        );
+        let max = Vec3::new(
+            max(corner.x, opposite_corner.x),
+            max(corner.y, opposite_corner.y),
+            max(corner.z, opposite_corner.z),
+        );
+        let width = 1 + max.x.abs_diff(min.x) as usize;
+        let height = 1 + max.y.abs_diff(min.y) as usize;
+        let depth = 1 + max.z.abs_diff(min.z) as usize;
+        Self {
+            min,
+            max,
+            width,
+            height,
+            depth,
+            cells: vec![empty_value; width * height * depth],
+        }
+    }
+
+    /// Size along the x axis
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Size along the y axis
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Size along the z axis
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Get a value by coordinate. Returns None if the coordinate is out-of-bounds.
+    pub fn get(&self, coordinate: Vec3<Index>) -> Option<V> {
+        let index = self.index_for(coordinate)?;
+        self.cells.get(index).cloned()
+    }
+
+    /// Set a value by coordinate. Returns None if the coordinate is out-of-bounds.
+    pub fn set(&mut self, coordinate: Vec3<Index>, value: V) -> Option<()> {
+        let index = self.index_for(coordinate)?;
+        self.cells[index] = value;
+        Some(())
+    }
+
+    pub fn contains(&self, coordinate: Vec3<Index>) -> bool {
+        self.index_for(coordinate).is_some()
+    }
+
+    fn index_for(&self, coordinate: Vec3<Index>) -> Option<usize> {
+        if coordinate.x < self.min.x
+            || coordinate.x > self.max.x
+            || coordinate.y < self.min.y
+            || coordinate.y > self.max.y
+            || coordinate.z < self.min.z
+            || coordinate.z > self.max.z
+        {
+            None
+        } else {
+            let layer = coordinate.z.abs_diff(self.min.z) as usize * self.width * self.height;
+            let row = coordinate.y.abs_diff(self.min.y) as usize * self.width;
+            let col = coordinate.x.abs_diff(self.min.x) as usize;
+            Some(layer + row + col)
+        }
+    }
+
+    fn point_for(&self, index: usize) -> Vec3<Index> {
+        let layer_size = self.width * self.height;
+        Vec3::new(
+            self.min.x + (index % self.width) as Index,
+            self.min.y + ((index % layer_size) / self.width) as Index,
+            self.min.z + (index / layer_size) as Index,
+        )
+    }
+
+    /// Iterate over every voxel, x fastest and z slowest
+    pub fn iter(&self) -> impl Iterator<Item = (Vec3<Index>, &V)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, value)| (self.point_for(i), value))
+    }
+
+    /// The in-bounds voxels which share a face with `coordinate`
+    pub fn neighbors6(&self, coordinate: Vec3<Index>) -> impl Iterator<Item = Vec3<Index>> + '_ {
+        FACES
+            .iter()
+            .map(move |&(x, y, z)| coordinate + Vec3::new(x, y, z))
+            .filter(|p| self.contains(*p))
+    }
+
+    /// The in-bounds voxels which share a face, edge or corner with `coordinate`
+    pub fn neighbors26(&self, coordinate: Vec3<Index>) -> impl Iterator<Item = Vec3<Index>> + '_ {
+        (-1..=1)
+            .flat_map(|z| (-1..=1).flat_map(move |y| (-1..=1).map(move |x| Vec3::new(x, y, z))))
+            .filter(|offset| *offset != Vec3::new(0, 0, 0))
+            .map(move |offset| coordinate + offset)
+            .filter(|p| self.contains(*p))
+    }
+
+    /// A view of the horizontal layer at height `z`, borrowing its voxels from
+    /// this grid. Returns None if `z` is out of bounds.
+    pub fn z_slice(&self, z: Index) -> Option<ZSlice<'_, V>> {
+        if z < self.min.z || z > self.max.z {
+            return None;
+        }
+        let layer_size = self.width * self.height;
+        let start = z.abs_diff(self.min.z) as usize * layer_size;
+        Some(ZSlice {
+            z,
+            min: Point::new(self.min.x, self.min.y),
+            max: Point::new(self.max.x, self.max.y),
+            width: self.width,
+            cells: &self.cells[start..start + layer_size],
+        })
+    }
+
+    /// Project the grid down onto the xy plane, recording the highest z for which
+    /// `occupied` returns true in each column (or None if the column is empty)
+    pub fn heightmap<F: Fn(&V) -> bool>(&self, occupied: F) -> DenseGrid<Option<Index>> {
+        let mut heights = DenseGrid::new_with(
+            Point::new(self.min.x, self.min.y),
+            Point::new(self.max.x, self.max.y),
+            None,
+        );
+        for (point, value) in self.iter() {
+            if occupied(value) {
+                heights[Point::new(point.x, point.y)] = Some(point.z);
+            }
+        }
+        heights
+    }
+}
+
+impl<V: Clone + fmt::Debug> std::ops::Index<Vec3<Index>> for DenseGrid3<V> {
+    type Output = V;
+
+    fn index(&self, coordinate: Vec3<Index>) -> &Self::Output {
+        let index = self.index_for(coordinate).unwrap();
+        self.cells.get(index).unwrap()
+    }
+}
+
+impl<V: Clone + fmt::Debug> std::ops::IndexMut<Vec3<Index>> for DenseGrid3<V> {
+    fn index_mut(&mut self, coordinate: Vec3<Index>) -> &mut Self::Output {
+        let index = self.index_for(coordinate).unwrap();
+        self.cells.get_mut(index).unwrap()
+    }
+}
+
+/// One horizontal layer of a [`DenseGrid3`], indexed by the x and y of its
+/// voxels. The voxels are borrowed rather than copied; use
+/// [`ZSlice::to_grid`] for an owned copy.
+#[derive(Debug)]
+pub struct ZSlice<'a, V> {
+    pub z: Index,
+    pub min: Point<Index>,
+    pub max: Point<Index>,
+    width: usize,
+    cells: &'a [V],
+}
+
+impl<V> Clone for ZSlice<'_, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for ZSlice<'_, V> {}
+
+impl<'a, V: Clone + fmt::Debug> ZSlice<'a, V> {
+    /// Size along the x axis
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Size along the y axis
+    pub fn height(&self) -> usize {
+        self.cells.len() / self.width
+    }
+
+    /// Get a value by its x and y. Returns None if the point is out-of-bounds.
+    pub fn get(&self, point: Point<Index>) -> Option<&'a V> {
+        if point.x < self.min.x
+            || point.x > self.max.x
+            || point.y < self.min.y
+            || point.y > self.max.y
+        {
+            return None;
+        }
+        let row = point.y.abs_diff(self.min.y) as usize * self.width;
+        let col = point.x.abs_diff(self.min.x) as usize;
+        self.cells.get(row + col)
+    }
+
+    /// Each row of the layer, from the lowest y up
+    pub fn rows(&self) -> impl Iterator<Item = &'a [V]> {
+        self.cells.chunks(self.width)
+    }
+
+    /// Iterate over every voxel in the layer, x fastest
+    pub fn iter(&self) -> impl Iterator<Item = (Point<Index>, &'a V)> {
+        let (min, width) = (self.min, self.width);
+        self.cells.iter().enumerate().map(move |(i, value)| {
+            let point = Point::new(min.x + (i % width) as Index, min.y + (i / width) as Index);
+            (point, value)
+        })
+    }
+
+    /// Copy the layer out into a 2D grid with the same x and y bounds
+    pub fn to_grid(&self) -> DenseGrid<V> {
+        let mut grid = DenseGrid::new_with(self.min, self.max, self.cells[0].clone());
+        for (point, value) in self.iter() {
+            grid[point] = value.clone();
+        }
+        grid
+    }
+}
+
+impl<V: Clone + fmt::Debug> std::ops::Index<Point<Index>> for ZSlice<'_, V> {
+    type Output = V;
+
+    fn index(&self, point: Point<Index>) -> &Self::Output {
+        self.get(point).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DenseGrid3, Vec3};
+    use crate::Point;
+
+    #[test]
+    fn test_basic() {
+        let mut g = DenseGrid3::new_with(Vec3::new(2, 2, 2), Vec3::new(-1, 0, 1), 0u8);
+        assert_eq!((g.width(), g.height(), g.depth()), (4, 3, 2));
+        assert_eq!(g.size(), 24);
+        assert_eq!(g.get(Vec3::new(0, 0, 0)), None);
+        g[Vec3::new(-1, 1, 2)] = 5;
+        assert_eq!(g.get(Vec3::new(-1, 1, 2)), Some(5));
+        let points = g.iter().map(|(p, _)| p).collect::<Vec<_>>();
+        assert_eq!(points.len(), 24);
+        assert_eq!(points[0], Vec3::new(-1, 0, 1));
+        assert_eq!(points[5], Vec3::new(0, 1, 1));
+        assert_eq!(points[23], Vec3::new(2, 2, 2));
+        assert_eq!(
+            g.iter().find(|(_, v)| **v == 5).map(|(p, _)| p),
+            Some(Vec3::new(-1, 1, 2))
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let g = DenseGrid3::new_with(Vec3::new(0, 0, 0), Vec3::new(2, 2, 2), 0u8);
+        assert_eq!(g.neighbors6(Vec3::new(1, 1, 1)).count(), 6);
+        assert_eq!(g.neighbors6(Vec3::new(0, 0, 0)).count(), 3);
+        assert_eq!(g.neighbors26(Vec3::new(1, 1, 1)).count(), 26);
+        assert_eq!(g.neighbors26(Vec3::new(0, 0, 0)).count(), 7);
+        assert!(!g
+            .neighbors26(Vec3::new(1, 1, 1))
+            .any(|p| p == Vec3::new(1, 1, 1)));
+    }
+
+    #[test]
+    fn test_slices() {
+        let mut g = DenseGrid3::new_with(Vec3::new(0, 0, 1), Vec3::new(2, 1, 3), '.');
+        g[Vec3::new(0, 0, 1)] = '#';
+        g[Vec3::new(0, 0, 3)] = '#';
+        g[Vec3::new(2, 1, 2)] = '#';
+        let slice = g.z_slice(2).unwrap();
+        assert_eq!((slice.z, slice.width(), slice.height()), (2, 3, 2));
+        assert_eq!(
+            slice.rows().collect::<Vec<_>>(),
+            vec![&['.', '.', '.'][..], &['.', '.', '#'][..]]
+        );
+        assert_eq!(slice[Point::new(2, 1)], '#');
+        assert_eq!(slice.get(Point::new(3, 1)), None);
+        assert_eq!(
+            slice.iter().filter(|(_, c)| **c == '#').collect::<Vec<_>>(),
+            vec![(Point::new(2, 1), &'#')]
+        );
+        assert_eq!(
+            slice.to_grid().rows().collect::<Vec<_>>(),
+            vec![vec!['.', '.', '.'], vec!['.', '.', '#']]
+        );
+        assert!(g.z_slice(0).is_none());
+
+        let heights = g.heightmap(|c| *c == '#');
+        assert_eq!(heights[Point::new(0, 0)], Some(3));
+        assert_eq!(heights[Point::new(2, 1)], Some(2));
+        assert_eq!(heights[Point::new(1, 0)], None);
+    }
+}
@@ -1,10 +1,38 @@
+pub mod compress;
+mod cuboid;
+pub mod cycle;
 mod dimval;
+mod direction;
+pub mod geom3;
 mod grid;
+mod grid3;
+pub mod linalg;
+pub mod memo;
 mod point;
+mod pointn;
+pub mod polygon;
+mod rangemap;
+mod rangeset;
+mod rect;
 mod vec3;
 
+pub use cuboid::Cuboid;
+pub use direction::Direction;
+pub use direction::Direction8;
+pub use grid::Change;
 pub use grid::DenseGrid;
+pub use grid::Fingerprint;
 pub use grid::HasEmpty;
+pub use grid::ParseGridError;
+pub use grid::Ragged;
+pub use grid::Snapshot;
+pub use grid3::DenseGrid3;
+pub use grid3::ZSlice;
 pub use point::Point;
 pub use point::Rotation;
+pub use point::Transform;
+pub use pointn::PointN;
+pub use rangemap::RangeMap;
+pub use rangeset::RangeSet;
+pub use rect::Rect;
 pub use vec3::Vec3;
@@ -0,0 +1,214 @@
+//! Exact solutions to small systems of linear equations.
+//!
+//! A system is a matrix of coefficients, one row per equation, and a vector of
+//! right hand sides. Solving is done by Gauss-Jordan elimination in whatever
+//! number type the system is given in; with a rational type like
+//! [`num::BigRational`] every step is exact, so there is no rounding to undo and
+//! no tolerance to pick when deciding whether a pivot is zero.
+
+use std::fmt;
+
+use num::{BigInt, BigRational, Num};
+
+/// Why a system has no single solution. A square system with a singular
+/// matrix always fails with one of these.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The equations contradict each other, so there is no solution
+    Inconsistent,
+    /// The equations only pin down `rank` of the `unknowns`, so there are
+    /// infinitely many solutions
+    Underdetermined { rank: usize, unknowns: usize },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Inconsistent => write!(f, "inconsistent system has no solution"),
+            SolveError::Underdetermined { rank, unknowns } => write!(
+                f,
+                "underdetermined system of rank {} in {} unknowns",
+                rank, unknowns
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Find the unique `x` with `coefficients * x = constants`. There may be more
+/// equations than unknowns, as long as the extra ones agree with the rest.
+///
+/// Panics if `coefficients` and `constants` have different lengths or the rows
+/// of `coefficients` are not all the same length.
+pub fn solve<T: Num + Clone>(
+    coefficients: &[Vec<T>],
+    constants: &[T],
+) -> Result<Vec<T>, SolveError> {
+    assert_eq!(
+        coefficients.len(),
+        constants.len(),
+        "need one constant per equation"
+    );
+    let unknowns = coefficients.first().map_or(0, |row| row.len());
+    assert!(
+        coefficients.iter().all(|row| row.len() == unknowns),
+        "every equation needs the same number of coefficients"
+    );
+
+    // the augmented matrix, with the constants as the last column
+    let mut rows = coefficients
+        .iter()
+        .zip(constants.iter())
+        .map(|(row, constant)| {
+            let mut row = row.clone();
+            row.push(constant.clone());
+            row
+        })
+        .collect::<Vec<_>>();
+
+    let mut pivots = vec![];
+    for column in 0..unknowns {
+        let rank = pivots.len();
+        let Some(found) = (rank..rows.len()).find(|r| !rows[*r][column].is_zero()) else {
+            continue;
+        };
+        rows.swap(rank, found);
+        let pivot = rows[rank][column].clone();
+        for value in rows[rank].iter_mut() {
+            *value = value.clone() / pivot.clone();
+        }
+        let pivot_row = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r == rank || row[column].is_zero() {
+                continue;
+            }
+            let factor = row[column].clone();
+            for (value, p) in row.iter_mut().zip(pivot_row.iter()) {
+                *value = value.clone() - factor.clone() * p.clone();
+            }
+        }
+        pivots.push(column);
+    }
+
+    // every row below the pivots has all-zero coefficients, so it only holds if
+    // its constant is zero too
+    if rows[pivots.len()..]
+        .iter()
+        .any(|row| !row[unknowns].is_zero())
+    {
+        return Err(SolveError::Inconsistent);
+    }
+    if pivots.len() < unknowns {
+        return Err(SolveError::Underdetermined {
+            rank: pivots.len(),
+            unknowns,
+        });
+    }
+    Ok(rows
+        .into_iter()
+        .take(unknowns)
+        .map(|mut row| row.pop().unwrap())
+        .collect())
+}
+
+/// Like [`solve`], for a system with integer coefficients, which is lifted to
+/// [`BigRational`] first so that nothing can overflow
+pub fn solve_integers<I: Clone + Into<BigInt>>(
+    coefficients: &[Vec<I>],
+    constants: &[I],
+) -> Result<Vec<BigRational>, SolveError> {
+    let lift = |value: &I| BigRational::from_integer(value.clone().into());
+    solve(
+        &coefficients
+            .iter()
+            .map(|row| row.iter().map(lift).collect())
+            .collect::<Vec<_>>(),
+        &constants.iter().map(lift).collect::<Vec<_>>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve, solve_integers, SolveError};
+    use num::{BigRational, Rational64};
+
+    fn r(n: i64, d: i64) -> Rational64 {
+        Rational64::new(n, d)
+    }
+
+    #[test]
+    fn test_unique() {
+        // the quadratic through (0, 3), (1, 6), (2, 11)
+        let x =
+            solve_integers(&[vec![0, 0, 1], vec![1, 1, 1], vec![4, 2, 1]], &[3, 6, 11]).unwrap();
+        assert_eq!(x, [1, 2, 3].map(|v| BigRational::from_integer(v.into())));
+
+        let x = solve(
+            &[vec![r(2, 1), r(1, 1)], vec![r(1, 1), r(3, 1)]],
+            &[r(1, 1), r(0, 1)],
+        )
+        .unwrap();
+        assert_eq!(x, vec![r(3, 5), r(-1, 5)]);
+    }
+
+    #[test]
+    fn test_needs_pivoting() {
+        // the first unknown is missing from the first equation
+        let x = solve(
+            &[vec![r(0, 1), r(1, 1)], vec![r(1, 1), r(1, 1)]],
+            &[r(2, 1), r(5, 1)],
+        )
+        .unwrap();
+        assert_eq!(x, vec![r(3, 1), r(2, 1)]);
+    }
+
+    #[test]
+    fn test_overdetermined() {
+        let coefficients = [vec![1, 0], vec![0, 1], vec![1, 1]];
+        let x = solve_integers(&coefficients, &[4, 5, 9]).unwrap();
+        assert_eq!(x[0], BigRational::from_integer(4.into()));
+        assert_eq!(x[1], BigRational::from_integer(5.into()));
+        assert_eq!(
+            solve_integers(&coefficients, &[4, 5, 10]),
+            Err(SolveError::Inconsistent)
+        );
+    }
+
+    #[test]
+    fn test_singular() {
+        let parallel = [vec![1, 2], vec![2, 4]];
+        assert_eq!(
+            solve_integers(&parallel, &[3, 7]),
+            Err(SolveError::Inconsistent)
+        );
+        assert_eq!(
+            solve_integers(&parallel, &[3, 6]),
+            Err(SolveError::Underdetermined {
+                rank: 1,
+                unknowns: 2
+            })
+        );
+        assert_eq!(
+            solve_integers(&[vec![1, 1, 1]], &[1]),
+            Err(SolveError::Underdetermined {
+                rank: 1,
+                unknowns: 3
+            })
+        );
+        assert_eq!(solve_integers::<i64>(&[], &[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_huge() {
+        // values around 4e14, whose squares overflow i64
+        let big = 400_000_000_000_000i64;
+        let x = solve_integers(
+            &[vec![big, 1], vec![big - 1, 1]],
+            &[big * 3 + 7, big * 3 - 3 + 7],
+        )
+        .unwrap();
+        assert_eq!(x[0], BigRational::from_integer(3.into()));
+        assert_eq!(x[1], BigRational::from_integer(7.into()));
+    }
+}
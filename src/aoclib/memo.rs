@@ -0,0 +1,201 @@
+//! Caching for recursive functions which would otherwise solve the same
+//! subproblem over and over.
+//!
+//! Any `Hash + Eq` value can be a key, so there is no need to squeeze the
+//! arguments into a compact encoding first. In particular, a function which
+//! recurses on shorter and shorter suffixes of a slice can key on how far into
+//! the slice it is, rather than on the contents of what is left.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// How often a [`Memo`] already had the answer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Stats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl Stats {
+    /// The fraction of lookups which were answered from the cache
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate)",
+            self.hits,
+            self.misses,
+            self.hit_rate() * 100.0
+        )
+    }
+}
+
+/// A cache of the values a function has already computed, keyed by its
+/// arguments
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    stats: Stats,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo {
+            cache: HashMap::new(),
+            stats: Stats::default(),
+        }
+    }
+
+    /// The cached value for `key`, or else the result of `compute`, which is
+    /// cached for next time. `compute` is handed the memo back so that it can
+    /// make recursive calls which share the same cache.
+    pub fn get_or_insert_with<F: FnOnce(&mut Self) -> V>(&mut self, key: K, compute: F) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+        self.stats.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// Evaluate the recursive function `f` at `key`. Rather than calling itself,
+    /// `f` calls the function it is passed, which checks the cache first.
+    pub fn recursive<F>(&mut self, key: K, f: &F) -> V
+    where
+        F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+    {
+        self.get_or_insert_with(key.clone(), |memo| {
+            f(&mut |next: K| memo.recursive(next, f), key)
+        })
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// The number of values cached
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// Forget every cached value and reset the statistics
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.stats = Stats::default();
+    }
+}
+
+/// Evaluate the recursive function `f` at `key` with a fresh [`Memo`], as with
+/// [`Memo::recursive`]
+pub fn memoize<K, V, F>(key: K, f: F) -> V
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    Memo::new().recursive(key, &f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{memoize, Memo, Stats};
+
+    fn fib(memo: &mut Memo<u64, u128>, n: u64) -> u128 {
+        memo.get_or_insert_with(n, |memo| {
+            if n < 2 {
+                n as u128
+            } else {
+                fib(memo, n - 1) + fib(memo, n - 2)
+            }
+        })
+    }
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let mut memo = Memo::new();
+        assert_eq!(fib(&mut memo, 150), 9969216677189303386214405760200);
+        assert_eq!(memo.len(), 151);
+        // every value is computed once, and looked up again once
+        assert_eq!(
+            memo.stats(),
+            Stats {
+                hits: 148,
+                misses: 151
+            }
+        );
+        assert_eq!(memo.get(&10), Some(&55));
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.stats(), Stats::default());
+    }
+
+    #[test]
+    fn test_recursive() {
+        // the number of ways to climb `n` stairs one or two at a time
+        let ways = |recurse: &mut dyn FnMut(u64) -> u128, n: u64| match n {
+            0 | 1 => 1,
+            _ => recurse(n - 1) + recurse(n - 2),
+        };
+        assert_eq!(memoize(90, ways), 4660046610375530309);
+
+        let mut memo = Memo::new();
+        assert_eq!(memo.recursive(10, &ways), 89);
+        assert_eq!(memo.stats().misses, 11);
+        assert_eq!(memo.recursive(10, &ways), 89);
+        assert_eq!(memo.stats().hits, 9);
+    }
+
+    #[test]
+    fn test_slice_offsets() {
+        // the number of ways to split a string into words from a dictionary,
+        // keyed only by how much of the string has been used up. A run of n a's
+        // splits in tribonacci(n) ways, and there are runs of 4 and 36
+        let words = ["a", "aa", "aaa", "b"];
+        let text = "aaaabaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+        let splits = memoize(0, |recurse: &mut dyn FnMut(usize) -> u64, offset: usize| {
+            if offset == text.len() {
+                return 1;
+            }
+            words
+                .iter()
+                .filter(|w| text[offset..].starts_with(*w))
+                .map(|w| recurse(offset + w.len()))
+                .sum()
+        });
+        assert_eq!(splits, 7 * 2082876103);
+    }
+
+    #[test]
+    fn test_stats_display() {
+        let stats = Stats { hits: 3, misses: 1 };
+        assert_eq!(stats.to_string(), "3 hits, 1 misses (75.0% hit rate)");
+        assert_eq!(Stats::default().hit_rate(), 0.0);
+    }
+}
@@ -1,27 +1,105 @@
-use crate::dimval::DimVal;
-use std::cmp::Ordering;
+use crate::dimval::{greater, lesser, manhattan_distance, DimVal, Widen};
+use crate::vec3::Vec3;
+use num_traits::{CheckedAdd, CheckedNeg, PrimInt, SaturatingMul};
 use std::fmt;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Rotation {
     CW,
     CCW,
     Mirror,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Hash, PartialOrd)]
-pub struct Point<I: DimVal = i64> {
-    pub x: I,
-    pub y: I,
+/// One of the eight symmetries of a square: the four rotations and the four
+/// reflections. As everywhere else, y grows downwards, so `RotateCW` takes
+/// (1, 0) to (0, 1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    RotateCW,
+    Rotate180,
+    RotateCCW,
+    /// Reflect across the x axis, turning the picture upside down: (x, y) -> (x, -y)
+    ReflectX,
+    /// Reflect across the y axis, swapping left and right: (x, y) -> (-x, y)
+    ReflectY,
+    /// Reflect across the `\` diagonal: (x, y) -> (y, x)
+    ReflectDiagonal,
+    /// Reflect across the `/` diagonal: (x, y) -> (-y, -x)
+    ReflectAntiDiagonal,
 }
 
-impl<I: DimVal + Eq> Eq for Point<I> {}
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::RotateCW,
+        Transform::Rotate180,
+        Transform::RotateCCW,
+        Transform::ReflectX,
+        Transform::ReflectY,
+        Transform::ReflectDiagonal,
+        Transform::ReflectAntiDiagonal,
+    ];
+
+    /// Apply this transform to a vector, treating the origin as the fixed point
+    pub fn apply<I: DimVal>(self, p: Point<I>) -> Point<I> {
+        let Point { x, y } = p;
+        match self {
+            Transform::Identity => Point::new(x, y),
+            Transform::RotateCW => Point::new(-y, x),
+            Transform::Rotate180 => Point::new(-x, -y),
+            Transform::RotateCCW => Point::new(y, -x),
+            Transform::ReflectX => Point::new(x, -y),
+            Transform::ReflectY => Point::new(-x, y),
+            Transform::ReflectDiagonal => Point::new(y, x),
+            Transform::ReflectAntiDiagonal => Point::new(-y, -x),
+        }
+    }
+
+    /// The transform which undoes this one
+    pub fn inverse(self) -> Self {
+        match self {
+            Transform::RotateCW => Transform::RotateCCW,
+            Transform::RotateCCW => Transform::RotateCW,
+            other => other,
+        }
+    }
+
+    /// The single transform equivalent to applying `self` and then `next`
+    pub fn then(self, next: Transform) -> Self {
+        let basis = |t: Transform| {
+            (
+                t.apply(Point::<i64>::new(1, 0)),
+                t.apply(Point::<i64>::new(0, 1)),
+            )
+        };
+        let wanted = (
+            next.apply(self.apply(Point::<i64>::new(1, 0))),
+            next.apply(self.apply(Point::<i64>::new(0, 1))),
+        );
+        Self::ALL
+            .into_iter()
+            .find(|t| basis(*t) == wanted)
+            .expect("the symmetries of a square form a group")
+    }
+}
 
-impl<I: DimVal + Ord> Ord for Point<I> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+impl From<Rotation> for Transform {
+    fn from(r: Rotation) -> Self {
+        match r {
+            Rotation::CW => Transform::RotateCW,
+            Rotation::CCW => Transform::RotateCCW,
+            Rotation::Mirror => Transform::Rotate180,
+        }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<I: DimVal = i64> {
+    pub x: I,
+    pub y: I,
+}
+
 impl<I: DimVal> Point<I> {
     pub const fn new(x: I, y: I) -> Self {
         Point { x, y }
@@ -35,30 +113,195 @@ impl<I: DimVal> Point<I> {
         Point::new(self.y, self.x)
     }
 
+    /// Panics if the distance does not fit in a usize; see
+    /// [`Point::checked_manhattan_distance_to`]
     pub fn manhattan_distance_to(&self, other: Point<I>) -> usize {
-        ((self.x - other.x).abs() + (self.y - other.y).abs())
-            .to_u64()
-            .unwrap() as usize
+        self.checked_manhattan_distance_to(other)
+            .expect("manhattan distance overflows usize")
+    }
+
+    /// The manhattan distance, or None if it does not fit in a usize. This never
+    /// overflows along the way, even for points at opposite ends of `i128`.
+    pub fn checked_manhattan_distance_to(&self, other: Point<I>) -> Option<usize> {
+        manhattan_distance([(self.x, other.x), (self.y, other.y)])
+    }
+
+    /// The taxicab length of this vector. Panics if it does not fit in a usize;
+    /// see [`Point::checked_manhattan`]
+    pub fn manhattan(&self) -> usize {
+        self.checked_manhattan()
+            .expect("manhattan length overflows usize")
+    }
+
+    /// The taxicab length, or None if it does not fit in a usize. Unlike adding
+    /// up `abs()` of each component, this cannot overflow at `MIN`.
+    pub fn checked_manhattan(&self) -> Option<usize> {
+        self.checked_manhattan_distance_to(Point::new(I::zero(), I::zero()))
+    }
+
+    /// Lift into three dimensions
+    pub fn with_z(&self, z: I) -> Vec3<I> {
+        Vec3::new(self.x, self.y, z)
+    }
+
+    pub fn abs(&self) -> Self {
+        Point::new(self.x.abs(), self.y.abs())
+    }
+
+    /// Each component replaced by -1, 0 or 1 according to its sign
+    pub fn signum(&self) -> Self {
+        Point::new(self.x.signum(), self.y.signum())
     }
 
+    /// The smaller of each pair of components
+    pub fn componentwise_min(&self, other: Point<I>) -> Self {
+        Point::new(lesser(self.x, other.x), lesser(self.y, other.y))
+    }
+
+    /// The larger of each pair of components
+    pub fn componentwise_max(&self, other: Point<I>) -> Self {
+        Point::new(greater(self.x, other.x), greater(self.y, other.y))
+    }
+
+    /// Rotate this vector by a multiple of 90 degrees around (0, 0)
     pub fn rotate_by(&self, r: Rotation) -> Self {
-        assert!(self.x == I::zero() || self.y == I::zero());
-        let neg_one = I::zero() - I::one();
-        match r {
-            Rotation::Mirror => Point::new(neg_one * self.x, neg_one * self.y),
-            Rotation::CCW => Point::new(self.y, neg_one * self.x),
-            Rotation::CW => Point::new(neg_one * self.y, self.x),
-        }
+        self.transform(r.into())
+    }
+
+    /// Rotate this point by a multiple of 90 degrees around `origin`
+    pub fn rotate_around(&self, origin: Point<I>, r: Rotation) -> Self {
+        self.transform_around(origin, r.into())
+    }
+
+    /// Rotate or reflect this vector, keeping (0, 0) fixed
+    pub fn transform(&self, t: Transform) -> Self {
+        t.apply(*self)
+    }
+
+    /// Rotate or reflect this point, keeping `origin` fixed
+    pub fn transform_around(&self, origin: Point<I>, t: Transform) -> Self {
+        t.apply(*self - origin) + origin
+    }
+}
+
+impl<I: Widen> Point<I> {
+    /// Convert each component to the wider type used for products
+    pub fn widen(&self) -> Point<I::Wide> {
+        Point::new(self.x.into(), self.y.into())
+    }
+
+    /// The dot product, computed in the wider type. Panics in the one case where
+    /// even that overflows; see [`Point::checked_dot`]
+    pub fn dot(&self, other: Point<I>) -> I::Wide {
+        self.checked_dot(other)
+            .expect("dot product overflows the wide type")
+    }
+
+    /// The dot product, or None if the sum of the products does not fit in the
+    /// wider type, which only happens when both are close to `MIN * MIN`
+    pub fn checked_dot(&self, other: Point<I>) -> Option<I::Wide> {
+        let (a, b) = (self.widen(), other.widen());
+        (a.x * b.x).checked_add(&(a.y * b.y))
+    }
+
+    /// The z component of the cross product of the two vectors treated as 3D,
+    /// computed in the wider type. This is the difference of two products, so it
+    /// is always exact.
+    pub fn cross(&self, other: Point<I>) -> I::Wide {
+        let (a, b) = (self.widen(), other.widen());
+        a.x * b.y - a.y * b.x
+    }
+}
+
+/// Arithmetic which reports or clamps overflow rather than wrapping
+impl<I: DimVal + PrimInt + CheckedNeg + SaturatingMul> Point<I> {
+    pub fn checked_add(&self, other: Point<I>) -> Option<Self> {
+        Some(Point::new(
+            self.x.checked_add(&other.x)?,
+            self.y.checked_add(&other.y)?,
+        ))
+    }
+
+    pub fn checked_sub(&self, other: Point<I>) -> Option<Self> {
+        Some(Point::new(
+            self.x.checked_sub(&other.x)?,
+            self.y.checked_sub(&other.y)?,
+        ))
+    }
+
+    pub fn checked_mul(&self, scalar: I) -> Option<Self> {
+        Some(Point::new(
+            self.x.checked_mul(&scalar)?,
+            self.y.checked_mul(&scalar)?,
+        ))
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Some(Point::new(self.x.checked_neg()?, self.y.checked_neg()?))
+    }
+
+    pub fn saturating_add(&self, other: Point<I>) -> Self {
+        Point::new(
+            self.x.saturating_add(other.x),
+            self.y.saturating_add(other.y),
+        )
+    }
+
+    pub fn saturating_sub(&self, other: Point<I>) -> Self {
+        Point::new(
+            self.x.saturating_sub(other.x),
+            self.y.saturating_sub(other.y),
+        )
+    }
+
+    pub fn saturating_mul(&self, scalar: I) -> Self {
+        Point::new(
+            self.x.saturating_mul(&scalar),
+            self.y.saturating_mul(&scalar),
+        )
     }
 }
 
 impl<I: DimVal + Ord> Point<I> {
+    /// Every point on the line from here to `other`, inclusive of both ends.
+    /// Horizontal, vertical and 45 degree lines are exact; any other slope is
+    /// rasterised with Bresenham's algorithm. A line to the same point yields just
+    /// that point.
     pub fn line_to(&self, other: Point<I>) -> impl Iterator<Item = Point<I>> {
         LineToIter::new(*self, other)
     }
+
+    /// Like [`Point::line_to`], but only for lines which are horizontal, vertical
+    /// or at 45 degrees, so that every step is a unit move. Returns None for any
+    /// other slope.
+    pub fn straight_line_to(&self, other: Point<I>) -> Option<impl Iterator<Item = Point<I>>> {
+        let delta = other - *self;
+        if delta.x.is_zero() || delta.y.is_zero() || delta.x.abs() == delta.y.abs() {
+            Some(LineToIter::new(*self, other))
+        } else {
+            None
+        }
+    }
+
+    /// Every point along the path through each of `vertices` in turn. Shared
+    /// vertices are only yielded once; to walk a closed loop, repeat the first
+    /// vertex at the end.
+    pub fn polyline<V: IntoIterator<Item = Point<I>>>(
+        vertices: V,
+    ) -> impl Iterator<Item = Point<I>> {
+        let vertices = vertices.into_iter().collect::<Vec<_>>();
+        let first = vertices.first().copied();
+        first.into_iter().chain(
+            vertices
+                .clone()
+                .into_iter()
+                .zip(vertices.into_iter().skip(1))
+                .flat_map(|(from, to)| from.line_to(to).skip(1)),
+        )
+    }
 }
 
-impl fmt::Display for Point {
+impl<I: DimVal> fmt::Display for Point<I> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "({}, {})", self.x, self.y)
     }
@@ -86,6 +329,35 @@ impl<I: DimVal> std::ops::Sub for Point<I> {
     }
 }
 
+impl<I: DimVal> std::ops::AddAssign for Point<I> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<I: DimVal> std::ops::SubAssign for Point<I> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<I: DimVal> std::ops::Neg for Point<I> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+/// Divide both components by a scalar. For integers this rounds towards zero.
+impl<I: DimVal> std::ops::Div<I> for Point<I> {
+    type Output = Self;
+
+    fn div(self, other: I) -> Self {
+        Point::new(self.x / other, self.y / other)
+    }
+}
+
 impl<I: DimVal> std::ops::Mul<I> for Point<I> {
     type Output = Self;
 
@@ -99,59 +371,59 @@ impl<I: DimVal> std::ops::Mul<I> for Point<I> {
 
 #[derive(Debug)]
 struct LineToIter<I: DimVal> {
-    start: Point<I>,
+    current: Point<I>,
     end: Point<I>,
-    direction: Point<I>,
+    step: Point<I>,
+    dx: I,
+    dy: I,
+    error: I,
     done: bool,
 }
 
 impl<I: DimVal + Ord> LineToIter<I> {
     fn new(start: Point<I>, end: Point<I>) -> Self {
-        debug_assert!(start.x == end.x || start.y == end.y);
-        let zero = I::zero();
-        let one = I::one();
-        let neg_one = one.neg();
-        let dir = match (start.x.cmp(&end.x), start.y.cmp(&end.y)) {
-            (Ordering::Less, _) => Point { x: one, y: zero },
-            (Ordering::Greater, _) => Point {
-                x: neg_one,
-                y: zero,
-            },
-            (Ordering::Equal, Ordering::Less) => Point { x: zero, y: one },
-            (Ordering::Equal, Ordering::Greater) => Point {
-                x: zero,
-                y: neg_one,
-            },
-            (Ordering::Equal, Ordering::Equal) => unreachable!(),
-        };
+        let dx = (end.x - start.x).abs();
+        let dy = -(end.y - start.y).abs();
         Self {
-            start,
+            current: start,
             end,
-            direction: dir,
+            step: Point::new((end.x - start.x).signum(), (end.y - start.y).signum()),
+            dx,
+            dy,
+            error: dx + dy,
             done: false,
         }
     }
 }
 
-impl<I: DimVal> Iterator for LineToIter<I> {
+impl<I: DimVal + Ord> Iterator for LineToIter<I> {
     type Item = Point<I>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.done {
             return None;
         }
-        let current = self.start;
-        if self.start == self.end {
-            self.done = true
+        let current = self.current;
+        if current == self.end {
+            self.done = true;
+            return Some(current);
+        }
+        let doubled = self.error + self.error;
+        if doubled >= self.dy {
+            self.error = self.error + self.dy;
+            self.current.x = self.current.x + self.step.x;
+        }
+        if doubled <= self.dx {
+            self.error = self.error + self.dx;
+            self.current.y = self.current.y + self.step.y;
         }
-        self.start = self.start + self.direction;
         Some(current)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Point, Rotation};
+    use super::{Point, Rotation, Transform};
 
     #[test]
     fn transpose() {
@@ -223,4 +495,248 @@ mod tests {
             Point::new(0, 1)
         );
     }
+
+    #[test]
+    fn test_rotate_diagonal() {
+        assert_eq!(Point::new(2, 3).rotate_by(Rotation::CW), Point::new(-3, 2));
+        assert_eq!(Point::new(2, 3).rotate_by(Rotation::CCW), Point::new(3, -2));
+        assert_eq!(
+            Point::new(2, 3).rotate_by(Rotation::Mirror),
+            Point::new(-2, -3)
+        );
+    }
+
+    #[test]
+    fn test_rotate_around() {
+        let origin = Point::new(10, 10);
+        assert_eq!(
+            Point::new(12, 10).rotate_around(origin, Rotation::CW),
+            Point::new(10, 12)
+        );
+        assert_eq!(
+            Point::new(12, 11).rotate_around(origin, Rotation::CCW),
+            Point::new(11, 8)
+        );
+        assert_eq!(origin.rotate_around(origin, Rotation::Mirror), origin);
+    }
+
+    #[test]
+    fn test_reflect() {
+        let p = Point::new(2, 3);
+        assert_eq!(p.transform(Transform::ReflectX), Point::new(2, -3));
+        assert_eq!(p.transform(Transform::ReflectY), Point::new(-2, 3));
+        assert_eq!(p.transform(Transform::ReflectDiagonal), Point::new(3, 2));
+        assert_eq!(
+            p.transform(Transform::ReflectAntiDiagonal),
+            Point::new(-3, -2)
+        );
+        assert_eq!(
+            p.transform_around(Point::new(0, 5), Transform::ReflectX),
+            Point::new(2, 7)
+        );
+        // a beam heading right hits a `/` mirror and heads up
+        assert_eq!(
+            Point::new(1, 0).transform(Transform::ReflectAntiDiagonal),
+            Point::new(0, -1)
+        );
+    }
+
+    #[test]
+    fn test_transform_group() {
+        let p = Point::new(2, 3);
+        for t in Transform::ALL {
+            assert_eq!(p.transform(t).transform(t.inverse()), p);
+            assert_eq!(t.then(t.inverse()), Transform::Identity);
+            for u in Transform::ALL {
+                assert_eq!(p.transform(t).transform(u), p.transform(t.then(u)));
+            }
+        }
+        assert_eq!(
+            Transform::RotateCW.then(Transform::RotateCW),
+            Transform::Rotate180
+        );
+        assert_eq!(
+            Transform::ReflectX.then(Transform::ReflectY),
+            Transform::Rotate180
+        );
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point::<i64>::new(3, -4);
+        let b = Point::new(-1, 2);
+        assert_eq!(-a, Point::new(-3, 4));
+        assert_eq!(a / 2, Point::new(1, -2));
+        assert_eq!(a.dot(b), -11);
+        assert_eq!(a.cross(b), 2);
+        assert_eq!(Point::<i64>::new(1, 0).cross(Point::new(0, 1)), 1);
+        assert_eq!(a.manhattan(), 7);
+        assert_eq!(a.abs(), Point::new(3, 4));
+        assert_eq!(a.signum(), Point::new(1, -1));
+        assert_eq!(a.componentwise_min(b), Point::new(-1, -4));
+        assert_eq!(a.componentwise_max(b), Point::new(3, 2));
+        let mut c = a;
+        c += b;
+        assert_eq!(c, Point::new(2, -2));
+        c -= b;
+        assert_eq!(c, a);
+        assert_eq!(Point::new(1.5, -2.0).to_string(), "(1.5, -2)");
+        assert_eq!(Point::<i32>::new(1, 2).to_string(), "(1, 2)");
+    }
+
+    #[test]
+    #[should_panic(expected = "dot product overflows")]
+    fn test_dot_overflow() {
+        let corner = Point::new(i64::MIN, i64::MIN);
+        corner.dot(corner);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let big = Point::new(i64::MAX, 0);
+        assert_eq!(big.checked_add(Point::new(1, 0)), None);
+        assert_eq!(
+            big.checked_add(Point::new(-1, 5)),
+            Some(Point::new(i64::MAX - 1, 5))
+        );
+        assert_eq!(big.checked_mul(2), None);
+        assert_eq!(Point::new(i64::MIN, 0).checked_neg(), None);
+        assert_eq!(
+            big.saturating_add(Point::new(1, -1)),
+            Point::new(i64::MAX, -1)
+        );
+        assert_eq!(big.saturating_mul(-2), Point::new(i64::MIN, 0));
+        assert_eq!(
+            Point::new(0, i64::MIN).saturating_sub(Point::new(0, 1)),
+            Point::new(0, i64::MIN)
+        );
+
+        // the products of i64 coordinates are computed exactly in i128
+        assert_eq!(big.dot(big), i64::MAX as i128 * i64::MAX as i128);
+        assert_eq!(
+            big.cross(Point::new(0, i64::MIN)),
+            i64::MAX as i128 * i64::MIN as i128
+        );
+        // a difference of two products always fits, even at the extremes...
+        let corner = Point::new(i64::MIN, i64::MIN);
+        assert_eq!(
+            corner.cross(Point::new(i64::MAX, i64::MIN)),
+            i128::MAX - (1 << 63) + 1
+        );
+        assert_eq!(
+            corner.cross(Point::new(i64::MIN, i64::MAX)),
+            i128::MIN + (1 << 63)
+        );
+        // ...but a sum of them only fits if they are not both MIN * MIN
+        assert_eq!(corner.checked_dot(corner), None);
+        let near = Point::new(i64::MIN, i64::MAX);
+        assert_eq!(near.checked_dot(near), Some(i128::MAX - (1 << 64) + 2));
+        assert_eq!(corner.checked_manhattan(), None);
+        assert_eq!(Point::new(i64::MIN, 0).manhattan(), 1 << 63);
+        assert_eq!(big.widen(), Point::new(i64::MAX as i128, 0));
+
+        let corner = Point::new(i64::MIN, i64::MIN);
+        let opposite = Point::new(i64::MAX, i64::MAX);
+        assert_eq!(corner.checked_manhattan_distance_to(opposite), None);
+        assert_eq!(
+            Point::new(i64::MIN, 0).manhattan_distance_to(Point::new(i64::MAX, 0)),
+            usize::MAX
+        );
+        let wide = Point::new(i128::MIN, 0);
+        assert_eq!(
+            wide.checked_manhattan_distance_to(Point::new(i128::MAX, 0)),
+            None
+        );
+        assert_eq!(wide.checked_add(wide), None);
+        // 4.5, rounded down once at the end
+        assert_eq!(
+            Point::new(0.5, 2.0).manhattan_distance_to(Point::new(3.0, 0.0)),
+            4
+        );
+        assert_eq!(
+            Point::new(0.5, 0.5).manhattan_distance_to(Point::new(1.0, 1.0)),
+            1
+        );
+        assert_eq!(
+            Point::new(f64::NAN, 0.0).checked_manhattan_distance_to(Point::new(1.0, 0.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_line_to_single() {
+        let p = Point::new(3, 4);
+        assert_eq!(p.line_to(p).collect::<Vec<_>>(), vec![p]);
+    }
+
+    #[test]
+    fn test_line_to_diagonal() {
+        let points = Point::new(0, 0)
+            .line_to(Point::new(3, -3))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            vec![
+                Point::new(0, 0),
+                Point::new(1, -1),
+                Point::new(2, -2),
+                Point::new(3, -3)
+            ]
+        );
+        assert!(Point::new(5, 5)
+            .straight_line_to(Point::new(1, 1))
+            .is_some());
+        assert!(Point::new(5, 5)
+            .straight_line_to(Point::new(5, 1))
+            .is_some());
+    }
+
+    #[test]
+    fn test_line_to_bresenham() {
+        let start = Point::new(0, 0);
+        let end = Point::new(6, 2);
+        let points = start.line_to(end).collect::<Vec<_>>();
+        assert_eq!(
+            points,
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 0),
+                Point::new(2, 1),
+                Point::new(3, 1),
+                Point::new(4, 1),
+                Point::new(5, 2),
+                Point::new(6, 2)
+            ]
+        );
+        assert!(start.straight_line_to(end).is_none());
+
+        // steep lines step once per row, and every step touches the previous point
+        let steep = Point::new(2i64, 9)
+            .line_to(Point::new(-1, 0))
+            .collect::<Vec<_>>();
+        assert_eq!(steep.len(), 10);
+        assert_eq!(steep.last(), Some(&Point::new(-1, 0)));
+        assert!(steep
+            .iter()
+            .zip(steep.iter().skip(1))
+            .all(|(a, b)| (a.x - b.x).abs() <= 1 && (a.y - b.y).abs() == 1));
+    }
+
+    #[test]
+    fn test_polyline() {
+        let square = [
+            Point::new(0, 0),
+            Point::new(2, 0),
+            Point::new(2, 2),
+            Point::new(0, 2),
+            Point::new(0, 0),
+        ];
+        let points = Point::polyline(square).collect::<Vec<_>>();
+        assert_eq!(points.len(), 9);
+        assert_eq!(points[0], Point::new(0, 0));
+        assert_eq!(points[3], Point::new(2, 1));
+        assert_eq!(points[8], Point::new(0, 0));
+        assert_eq!(Point::<i64>::polyline([]).count(), 0);
+        assert_eq!(Point::polyline([Point::new(1, 1)]).count(), 1);
+    }
 }
@@ -0,0 +1,297 @@
+use super::dimval::{greater, lesser, manhattan_distance, DimVal, Widen};
+use super::point::Point;
+use super::vec3::Vec3;
+use num_traits::{CheckedAdd, Zero};
+use std::fmt;
+
+/// A point or vector with any number of dimensions, for algorithms which treat
+/// every axis the same way. [`Point`] and [`Vec3`] convert to and from the two
+/// and three dimensional versions for free.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PointN<T: DimVal, const N: usize>(pub [T; N]);
+
+impl<T: DimVal, const N: usize> PointN<T, N> {
+    pub fn new(components: [T; N]) -> Self {
+        PointN(components)
+    }
+
+    /// Build a point by evaluating `f` on each axis number in turn
+    pub fn from_fn<F: FnMut(usize) -> T>(f: F) -> Self {
+        PointN(std::array::from_fn(f))
+    }
+
+    pub fn zero() -> Self {
+        PointN([T::zero(); N])
+    }
+
+    /// The unit vector pointing along the positive direction of `axis`
+    pub fn unit(axis: usize) -> Self {
+        assert!(axis < N, "axis {} out of range for {} dimensions", axis, N);
+        Self::from_fn(|i| if i == axis { T::one() } else { T::zero() })
+    }
+
+    /// The number of dimensions
+    pub const fn dimensions(&self) -> usize {
+        N
+    }
+
+    pub fn components(&self) -> impl Iterator<Item = T> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Apply `f` to every component
+    pub fn map<F: Fn(T) -> T>(&self, f: F) -> Self {
+        Self::from_fn(|i| f(self.0[i]))
+    }
+
+    /// Combine two points component by component
+    pub fn zip_with<F: Fn(T, T) -> T>(&self, other: PointN<T, N>, f: F) -> Self {
+        Self::from_fn(|i| f(self.0[i], other.0[i]))
+    }
+
+    /// The taxicab length of this vector. Panics if it does not fit in a usize;
+    /// see [`PointN::checked_manhattan`]
+    pub fn manhattan(&self) -> usize {
+        self.checked_manhattan()
+            .expect("manhattan length overflows usize")
+    }
+
+    /// The taxicab length, or None if it does not fit in a usize
+    pub fn checked_manhattan(&self) -> Option<usize> {
+        self.checked_manhattan_distance_to(Self::zero())
+    }
+
+    /// Panics if the distance does not fit in a usize; see
+    /// [`PointN::checked_manhattan_distance_to`]
+    pub fn manhattan_distance_to(&self, other: PointN<T, N>) -> usize {
+        self.checked_manhattan_distance_to(other)
+            .expect("manhattan distance overflows usize")
+    }
+
+    /// The manhattan distance, or None if it does not fit in a usize
+    pub fn checked_manhattan_distance_to(&self, other: PointN<T, N>) -> Option<usize> {
+        manhattan_distance(self.components().zip(other.components()))
+    }
+
+    pub fn abs(&self) -> Self {
+        self.map(|c| c.abs())
+    }
+
+    /// Each component replaced by -1, 0 or 1 according to its sign
+    pub fn signum(&self) -> Self {
+        self.map(|c| c.signum())
+    }
+
+    /// The smaller of each pair of components
+    pub fn componentwise_min(&self, other: PointN<T, N>) -> Self {
+        self.zip_with(other, lesser)
+    }
+
+    /// The larger of each pair of components
+    pub fn componentwise_max(&self, other: PointN<T, N>) -> Self {
+        self.zip_with(other, greater)
+    }
+
+    /// The 2N points one step away along a single axis
+    pub fn orthogonal_neighbors(&self) -> impl Iterator<Item = Self> + '_ {
+        (0..N).flat_map(move |axis| {
+            let step = Self::unit(axis);
+            [*self - step, *self + step]
+        })
+    }
+}
+
+impl<T: Widen, const N: usize> PointN<T, N> {
+    /// Convert each component to the wider type used for products
+    pub fn widen(&self) -> PointN<T::Wide, N> {
+        PointN(self.0.map(Into::into))
+    }
+
+    /// The dot product, computed in the wider type. Panics if even that
+    /// overflows; see [`PointN::checked_dot`]
+    pub fn dot(&self, other: PointN<T, N>) -> T::Wide {
+        self.checked_dot(other)
+            .expect("dot product overflows the wide type")
+    }
+
+    /// The dot product, or None if the sum of the products does not fit in the
+    /// wider type
+    pub fn checked_dot(&self, other: PointN<T, N>) -> Option<T::Wide> {
+        self.widen()
+            .components()
+            .zip(other.widen().components())
+            .try_fold(T::Wide::zero(), |total, (a, b)| total.checked_add(&(a * b)))
+    }
+}
+
+impl<T: DimVal, const N: usize> std::ops::Index<usize> for PointN<T, N> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &T {
+        &self.0[axis]
+    }
+}
+
+impl<T: DimVal, const N: usize> std::ops::IndexMut<usize> for PointN<T, N> {
+    fn index_mut(&mut self, axis: usize) -> &mut T {
+        &mut self.0[axis]
+    }
+}
+
+impl<T: DimVal, const N: usize> std::ops::Add for PointN<T, N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a + b)
+    }
+}
+
+impl<T: DimVal, const N: usize> std::ops::AddAssign for PointN<T, N> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: DimVal, const N: usize> std::ops::Sub for PointN<T, N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a - b)
+    }
+}
+
+impl<T: DimVal, const N: usize> std::ops::SubAssign for PointN<T, N> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T: DimVal, const N: usize> std::ops::Neg for PointN<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.map(|c| -c)
+    }
+}
+
+impl<T: DimVal, const N: usize> std::ops::Mul<T> for PointN<T, N> {
+    type Output = Self;
+
+    fn mul(self, scalar: T) -> Self {
+        self.map(|c| c * scalar)
+    }
+}
+
+/// Divide every component by a scalar. For integers this rounds towards zero.
+impl<T: DimVal, const N: usize> std::ops::Div<T> for PointN<T, N> {
+    type Output = Self;
+
+    fn div(self, scalar: T) -> Self {
+        self.map(|c| c / scalar)
+    }
+}
+
+impl<T: DimVal, const N: usize> fmt::Display for PointN<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, c) in self.components().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", c)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<T: DimVal> From<Point<T>> for PointN<T, 2> {
+    fn from(p: Point<T>) -> Self {
+        PointN([p.x, p.y])
+    }
+}
+
+impl<T: DimVal> From<PointN<T, 2>> for Point<T> {
+    fn from(p: PointN<T, 2>) -> Self {
+        let [x, y] = p.0;
+        Point::new(x, y)
+    }
+}
+
+impl<T: DimVal> From<Vec3<T>> for PointN<T, 3> {
+    fn from(v: Vec3<T>) -> Self {
+        PointN([v.x, v.y, v.z])
+    }
+}
+
+impl<T: DimVal> From<PointN<T, 3>> for Vec3<T> {
+    fn from(p: PointN<T, 3>) -> Self {
+        let [x, y, z] = p.0;
+        Vec3::new(x, y, z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PointN;
+    use crate::{Point, Vec3};
+
+    #[test]
+    fn test_arithmetic() {
+        let a = PointN::<i64, 4>::new([1, -2, 3, 4]);
+        let b = PointN::new([0, 5, -6, 1]);
+        assert_eq!(a + b, PointN::new([1, 3, -3, 5]));
+        assert_eq!(a - b, PointN::new([1, -7, 9, 3]));
+        assert_eq!(-a, PointN::new([-1, 2, -3, -4]));
+        assert_eq!(a * 2, PointN::new([2, -4, 6, 8]));
+        assert_eq!(a / 2, PointN::new([0, -1, 1, 2]));
+        assert_eq!(a.dot(b), -24);
+        assert_eq!(a.manhattan(), 10);
+        assert_eq!(a.manhattan_distance_to(b), 20);
+        assert_eq!(a.componentwise_min(b), PointN::new([0, -2, -6, 1]));
+        assert_eq!(a.componentwise_max(b), PointN::new([1, 5, 3, 4]));
+        assert_eq!(a.signum(), PointN::new([1, -1, 1, 1]));
+        assert_eq!(a.to_string(), "(1, -2, 3, 4)");
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let corner = PointN::<i64, 2>::new([i64::MIN, i64::MIN]);
+        assert_eq!(corner.checked_dot(corner), None);
+        assert_eq!(
+            corner.checked_dot(PointN::new([i64::MIN, 0])),
+            Some(i64::MIN as i128 * i64::MIN as i128)
+        );
+        assert_eq!(corner.checked_manhattan(), None);
+        assert_eq!(PointN::new([i64::MIN, 0]).manhattan(), 1 << 63);
+    }
+
+    #[test]
+    fn test_axes() {
+        let mut p = PointN::<i64, 3>::zero();
+        for axis in 0..p.dimensions() {
+            p[axis] = axis as i64 * 10;
+        }
+        assert_eq!(p, PointN::new([0, 10, 20]));
+        assert_eq!(p.components().sum::<i64>(), 30);
+        assert_eq!(PointN::<i32, 3>::unit(1), PointN::new([0, 1, 0]));
+        let neighbors = p.orthogonal_neighbors().collect::<Vec<_>>();
+        assert_eq!(neighbors.len(), 6);
+        assert!(neighbors.iter().all(|n| n.manhattan_distance_to(p) == 1));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let p = Point::new(3, -4);
+        let n = PointN::from(p);
+        assert_eq!(n, PointN::new([3, -4]));
+        assert_eq!(Point::from(n), p);
+        let v = Vec3::new(1, 2, 3);
+        let n = PointN::from(v);
+        assert_eq!(n[2], 3);
+        assert_eq!(Vec3::from(n * 2), v * 2);
+    }
+}
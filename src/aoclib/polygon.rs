@@ -0,0 +1,202 @@
+//! Exact measurements of simple polygons on the integer lattice.
+//!
+//! A polygon is a slice of its vertices in order, either clockwise or
+//! counter-clockwise. The last vertex implicitly joins back up to the first;
+//! repeating the first vertex at the end is harmless. Edges may run in any
+//! direction, not just along the axes. Sums are accumulated in `i128` so that
+//! puzzle-sized coordinates cannot overflow.
+
+use std::cmp::Ordering;
+
+use num::BigInt;
+
+use super::point::Point;
+
+/// Where a point lies relative to a polygon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices
+        .iter()
+        .copied()
+        .zip(vertices.iter().copied().cycle().skip(1))
+}
+
+/// Twice the enclosed area, computed with the shoelace formula. Lattice
+/// polygons can have a half-integer area, but twice the area is always exact.
+pub fn double_area(vertices: &[Point]) -> i128 {
+    edges(vertices).map(|(a, b)| a.cross(b)).sum::<i128>().abs()
+}
+
+/// The number of lattice points lying on the edges of the polygon, which for
+/// an axis-aligned polygon is also its perimeter
+pub fn boundary_points(vertices: &[Point]) -> i128 {
+    edges(vertices)
+        .map(|(a, b)| {
+            let (a, b) = (a.widen(), b.widen());
+            num::integer::gcd(b.x - a.x, b.y - a.y)
+        })
+        .sum()
+}
+
+/// The number of lattice points strictly inside the polygon, by Pick's theorem
+pub fn interior_points(vertices: &[Point]) -> i128 {
+    (double_area(vertices) - boundary_points(vertices) + 2) / 2
+}
+
+/// The number of lattice points inside the polygon or on its edges. When every
+/// vertex is the centre of a grid cell, this is the number of cells covered.
+pub fn lattice_points(vertices: &[Point]) -> i128 {
+    interior_points(vertices) + boundary_points(vertices)
+}
+
+/// The sign of the cross product of `b - a` with `p - a`, which is zero when `p`
+/// lies on the line through `a` and `b` and otherwise tells you which side of it
+/// `p` is on. The differences are taken after widening to `i128`, where their
+/// products can still overflow, so those fall back to a [`BigInt`].
+fn cross(p: Point, a: Point, b: Point) -> Ordering {
+    let (p, a, b) = (p.widen(), a.widen(), b.widen());
+    let (u, v) = (b - a, p - a);
+    match (u.x.checked_mul(v.y), u.y.checked_mul(v.x)) {
+        (Some(left), Some(right)) => left.cmp(&right),
+        _ => (BigInt::from(u.x) * v.y).cmp(&(BigInt::from(u.y) * v.x)),
+    }
+}
+
+fn on_segment(p: Point, a: Point, b: Point) -> bool {
+    cross(p, a, b) == Ordering::Equal
+        && p.x >= a.x.min(b.x)
+        && p.x <= a.x.max(b.x)
+        && p.y >= a.y.min(b.y)
+        && p.y <= a.y.max(b.y)
+}
+
+/// Classify a point as inside, outside or on the edge of the polygon
+pub fn locate(vertices: &[Point], p: Point) -> Location {
+    let mut inside = false;
+    for (a, b) in edges(vertices) {
+        if on_segment(p, a, b) {
+            return Location::Boundary;
+        }
+        // count crossings of a ray heading in the +x direction, treating each
+        // edge as half-open in y so that vertices are not counted twice
+        if (a.y > p.y) != (b.y > p.y) && (cross(p, a, b) == Ordering::Greater) == (b.y > a.y) {
+            inside = !inside;
+        }
+    }
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+/// Whether a point is inside the polygon or on its boundary
+pub fn contains(vertices: &[Point], p: Point) -> bool {
+    locate(vertices, p) != Location::Outside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        boundary_points, contains, double_area, interior_points, lattice_points, locate, Location,
+    };
+    use crate::Point;
+
+    fn rectangle(w: i64, h: i64) -> Vec<Point> {
+        vec![
+            Point::new(0, 0),
+            Point::new(w, 0),
+            Point::new(w, h),
+            Point::new(0, h),
+        ]
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let r = rectangle(4, 3);
+        assert_eq!(double_area(&r), 24);
+        assert_eq!(boundary_points(&r), 14);
+        assert_eq!(interior_points(&r), 6);
+        assert_eq!(lattice_points(&r), 20);
+
+        let mut closed = r.clone();
+        closed.push(r[0]);
+        assert_eq!(double_area(&closed), 24);
+        assert_eq!(lattice_points(&closed), 20);
+
+        let mut reversed = r.clone();
+        reversed.reverse();
+        assert_eq!(double_area(&reversed), 24);
+    }
+
+    #[test]
+    fn test_triangle() {
+        let t = [Point::new(0, 0), Point::new(3, 0), Point::new(0, 3)];
+        assert_eq!(double_area(&t), 9);
+        assert_eq!(boundary_points(&t), 9);
+        assert_eq!(interior_points(&t), 1);
+    }
+
+    #[test]
+    fn test_huge() {
+        let r = rectangle(4_000_000_000, 4_000_000_000);
+        assert_eq!(double_area(&r), 32_000_000_000_000_000_000);
+        assert_eq!(lattice_points(&r), 4_000_000_001 * 4_000_000_001);
+    }
+
+    #[test]
+    fn test_extreme_coordinates() {
+        // edges spanning the whole of i64, whose lengths do not fit in one
+        let t = [
+            Point::new(i64::MIN, i64::MIN),
+            Point::new(i64::MAX, i64::MIN),
+            Point::new(i64::MIN, i64::MAX),
+        ];
+        let span = u64::MAX as i128;
+        assert_eq!(boundary_points(&t), 3 * span);
+        assert_eq!(locate(&t, Point::new(-1, -1)), Location::Inside);
+        assert_eq!(locate(&t, Point::new(0, -1)), Location::Boundary);
+        assert_eq!(locate(&t, Point::new(0, 0)), Location::Outside);
+        assert_eq!(
+            locate(&t, Point::new(i64::MAX, i64::MAX)),
+            Location::Outside
+        );
+        assert_eq!(locate(&t, Point::new(i64::MIN, 0)), Location::Boundary);
+    }
+
+    #[test]
+    fn test_locate() {
+        // a U shape with a notch cut into the top
+        let u = [
+            Point::new(0, 0),
+            Point::new(2, 0),
+            Point::new(2, 3),
+            Point::new(4, 3),
+            Point::new(4, 0),
+            Point::new(6, 0),
+            Point::new(6, 5),
+            Point::new(0, 5),
+        ];
+        assert_eq!(locate(&u, Point::new(1, 1)), Location::Inside);
+        assert_eq!(locate(&u, Point::new(3, 1)), Location::Outside);
+        assert_eq!(locate(&u, Point::new(3, 4)), Location::Inside);
+        assert_eq!(locate(&u, Point::new(3, 3)), Location::Boundary);
+        assert_eq!(locate(&u, Point::new(2, 0)), Location::Boundary);
+        assert_eq!(locate(&u, Point::new(7, 3)), Location::Outside);
+        assert_eq!(locate(&u, Point::new(-1, 3)), Location::Outside);
+        assert!(contains(&u, Point::new(0, 5)));
+        assert!(!contains(&u, Point::new(5, 6)));
+
+        let inside = (-1..=7)
+            .flat_map(|x| (-1..=6).map(move |y| Point::new(x, y)))
+            .filter(|p| locate(&u, *p) == Location::Inside)
+            .count();
+        assert_eq!(inside as i128, interior_points(&u));
+    }
+}
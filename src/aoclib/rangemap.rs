@@ -0,0 +1,243 @@
+use std::ops::Range;
+
+/// A map from integers to integers which is built from pieces, each of which
+/// translates a half-open range of inputs by a constant offset. Anything not
+/// covered by a piece maps to itself.
+///
+/// Because the map is defined everywhere, maps can be chained together with
+/// [`RangeMap::then`] into a single map with the same shape, and maps which
+/// never send two inputs to the same output can be run backwards with
+/// [`RangeMap::inverse`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeMap {
+    /// `(start, offset)` pairs sorted by start. Each piece runs up to the start of
+    /// the next, and the first always starts at `i64::MIN`.
+    pieces: Vec<(i64, i64)>,
+}
+
+impl Default for RangeMap {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl RangeMap {
+    /// The map which sends everything to itself
+    pub fn identity() -> Self {
+        RangeMap {
+            pieces: vec![(i64::MIN, 0)],
+        }
+    }
+
+    /// Build a map from `(source, destination)` pairs, as with [`RangeMap::insert`]
+    pub fn from_ranges<R: IntoIterator<Item = (Range<i64>, i64)>>(ranges: R) -> Self {
+        let mut map = Self::identity();
+        for (source, destination) in ranges {
+            map.insert(source, destination);
+        }
+        map
+    }
+
+    /// The exclusive end of the piece at `index`, widened so that the last piece
+    /// can end after `i64::MAX`
+    fn end_of(&self, index: usize) -> i128 {
+        self.pieces
+            .get(index + 1)
+            .map_or(i64::MAX as i128 + 1, |(start, _)| *start as i128)
+    }
+
+    fn pieces(&self) -> impl Iterator<Item = (i64, i128, i64)> + '_ {
+        self.pieces
+            .iter()
+            .enumerate()
+            .map(|(i, (start, offset))| (*start, self.end_of(i), *offset))
+    }
+
+    fn piece_containing(&self, value: i64) -> usize {
+        self.pieces.partition_point(|(start, _)| *start <= value) - 1
+    }
+
+    /// Make sure a piece starts exactly at `at`
+    fn split_at(&mut self, at: i64) {
+        let index = self.piece_containing(at);
+        if self.pieces[index].0 != at {
+            self.pieces.insert(index + 1, (at, self.pieces[index].1));
+        }
+    }
+
+    /// Merge neighbouring pieces which share an offset
+    fn normalise(&mut self) {
+        self.pieces.dedup_by(|next, previous| next.1 == previous.1);
+    }
+
+    /// Send `source.start + n` to `destination + n` for every `source.start + n`
+    /// in `source`, replacing whatever those inputs used to map to
+    pub fn insert(&mut self, source: Range<i64>, destination: i64) {
+        if source.is_empty() {
+            return;
+        }
+        self.split_at(source.start);
+        self.split_at(source.end);
+        let offset = destination - source.start;
+        for piece in self.pieces.iter_mut() {
+            if source.contains(&piece.0) {
+                piece.1 = offset;
+            }
+        }
+        self.normalise();
+    }
+
+    pub fn get(&self, value: i64) -> i64 {
+        value + self.pieces[self.piece_containing(value)].1
+    }
+
+    /// The image of a range of inputs, as one output range per piece it crosses,
+    /// in the order of the inputs which produced them
+    pub fn map_range(&self, range: Range<i64>) -> Vec<Range<i64>> {
+        if range.is_empty() {
+            return vec![];
+        }
+        let first = self.piece_containing(range.start);
+        self.pieces()
+            .skip(first)
+            .take_while(|(start, _, _)| *start < range.end)
+            .map(|(start, end, offset)| {
+                let from = std::cmp::max(start, range.start);
+                let to = std::cmp::min(end, range.end as i128) as i64;
+                from + offset..to + offset
+            })
+            .collect()
+    }
+
+    /// The smallest output for any input in `range`, or None if it is empty
+    pub fn min_over(&self, range: Range<i64>) -> Option<i64> {
+        self.map_range(range).into_iter().map(|r| r.start).min()
+    }
+
+    /// Every input at which the offset changes, in ascending order
+    pub fn breakpoints(&self) -> impl Iterator<Item = i64> + '_ {
+        self.pieces.iter().skip(1).map(|(start, _)| *start)
+    }
+
+    /// The map which applies this map and then `next`
+    pub fn then(&self, next: &RangeMap) -> RangeMap {
+        let mut pieces = vec![];
+        for (start, end, offset) in self.pieces() {
+            let image_start = start as i128 + offset as i128;
+            let image_end = end + offset as i128;
+            for (next_start, next_end, next_offset) in next.pieces() {
+                if (next_start as i128) < image_end && next_end > image_start {
+                    let from = std::cmp::max(image_start, next_start as i128) - offset as i128;
+                    pieces.push((from as i64, offset + next_offset));
+                }
+            }
+        }
+        let mut composed = RangeMap { pieces };
+        composed.normalise();
+        composed
+    }
+
+    /// The map which undoes this one, or None if two inputs share an output
+    pub fn inverse(&self) -> Option<RangeMap> {
+        let mut images = self
+            .pieces()
+            .map(|(start, end, offset)| {
+                (start as i128 + offset as i128, end + offset as i128, offset)
+            })
+            .collect::<Vec<_>>();
+        images.sort_unstable();
+        let mut expected = i64::MIN as i128;
+        for (image_start, image_end, _) in images.iter() {
+            if *image_start != expected {
+                return None;
+            }
+            expected = *image_end;
+        }
+        if expected != i64::MAX as i128 + 1 {
+            return None;
+        }
+        let mut inverse = RangeMap {
+            pieces: images
+                .into_iter()
+                .map(|(image_start, _, offset)| (image_start as i64, -offset))
+                .collect(),
+        };
+        inverse.normalise();
+        Some(inverse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RangeMap;
+
+    fn seed_to_soil() -> RangeMap {
+        RangeMap::from_ranges([(98..100, 50), (50..98, 52)])
+    }
+
+    fn soil_to_fertilizer() -> RangeMap {
+        RangeMap::from_ranges([(15..52, 0), (52..54, 37), (0..15, 39)])
+    }
+
+    #[test]
+    fn test_get() {
+        let m = seed_to_soil();
+        assert_eq!(m.get(79), 81);
+        assert_eq!(m.get(14), 14);
+        assert_eq!(m.get(98), 50);
+        assert_eq!(m.get(99), 51);
+        assert_eq!(m.get(100), 100);
+        assert_eq!(m.breakpoints().collect::<Vec<_>>(), vec![50, 98, 100]);
+        assert_eq!(RangeMap::identity().get(i64::MAX), i64::MAX);
+    }
+
+    #[test]
+    fn test_insert_overlapping() {
+        let mut m = RangeMap::from_ranges([(0..10, 100)]);
+        m.insert(5..15, 205);
+        assert_eq!(m.get(4), 104);
+        assert_eq!(m.get(5), 205);
+        assert_eq!(m.get(14), 214);
+        assert_eq!(m.get(15), 15);
+        // re-inserting the identity removes the breakpoints entirely
+        m.insert(0..15, 0);
+        assert_eq!(m, RangeMap::identity());
+    }
+
+    #[test]
+    fn test_map_range() {
+        let m = seed_to_soil();
+        assert_eq!(m.map_range(45..55), vec![45..50, 52..57]);
+        assert_eq!(m.map_range(97..102), vec![99..100, 50..52, 100..102]);
+        assert_eq!(m.map_range(3..3), vec![]);
+        assert_eq!(m.min_over(90..101), Some(50));
+    }
+
+    #[test]
+    fn test_then() {
+        let (a, b) = (seed_to_soil(), soil_to_fertilizer());
+        let composed = a.then(&b);
+        for seed in -5..120 {
+            assert_eq!(composed.get(seed), b.get(a.get(seed)), "seed {}", seed);
+        }
+        assert_eq!(composed.get(79), 81);
+        assert_eq!(composed.get(14), 53);
+        assert_eq!(RangeMap::identity().then(&a), a);
+        assert_eq!(a.then(&RangeMap::identity()), a);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let (a, b) = (seed_to_soil(), soil_to_fertilizer());
+        let composed = a.then(&b);
+        let inverse = composed.inverse().unwrap();
+        for seed in -5..120 {
+            assert_eq!(inverse.get(composed.get(seed)), seed);
+        }
+        assert_eq!(composed.then(&inverse), RangeMap::identity());
+
+        // 0..10 moves onto 5..15, on top of the untouched 10..15
+        let squashed = RangeMap::from_ranges([(0..10, 5)]);
+        assert_eq!(squashed.inverse(), None);
+    }
+}
@@ -0,0 +1,331 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use num_traits::PrimInt;
+
+/// A set of integers, stored as a sorted list of disjoint inclusive ranges.
+/// Ranges which overlap or touch are always merged, so two sets containing the
+/// same integers compare equal however they were built.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T: PrimInt> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: PrimInt> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PrimInt> RangeSet<T> {
+    /// The empty set
+    pub fn new() -> Self {
+        RangeSet { ranges: vec![] }
+    }
+
+    /// The set of every integer in `range`
+    pub fn from_range(range: RangeInclusive<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+
+    /// Sort and merge a list of ranges which may overlap, touch or be empty
+    fn normalised(mut ranges: Vec<(T, T)>) -> Self {
+        ranges.retain(|(start, end)| start <= end);
+        ranges.sort_unstable();
+        let mut merged: Vec<(T, T)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some((_, last_end))
+                    if last_end
+                        .checked_add(&T::one())
+                        .is_none_or(|next| start <= next) =>
+                {
+                    *last_end = std::cmp::max(*last_end, end);
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+        RangeSet { ranges: merged }
+    }
+
+    /// Add every integer in `range` to the set
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.push(range.into_inner());
+        *self = Self::normalised(ranges);
+    }
+
+    /// Remove every integer in `range` from the set
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        *self = self.difference(&Self::from_range(range));
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.ranges.partition_point(|(_, end)| *end < value);
+        self.ranges
+            .get(index)
+            .is_some_and(|(start, _)| *start <= value)
+    }
+
+    /// The number of integers in the set, or None if there are too many to
+    /// count in a usize
+    pub fn len(&self) -> Option<usize> {
+        self.ranges.iter().try_fold(0usize, |total, (start, end)| {
+            total.checked_add(usize::try_from(span(*start, *end)?).ok()?)
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|(start, _)| *start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|(_, end)| *end)
+    }
+
+    /// The disjoint ranges making up the set, in ascending order
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|(start, end)| *start..=*end)
+    }
+
+    /// Every integer in the set, in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.ranges
+            .iter()
+            .flat_map(|(start, end)| num::iter::range_inclusive(*start, *end))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalised(
+            self.ranges
+                .iter()
+                .chain(other.ranges.iter())
+                .copied()
+                .collect(),
+        )
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut out = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[i];
+            let (b_start, b_end) = other.ranges[j];
+            let start = std::cmp::max(a_start, b_start);
+            let end = std::cmp::min(a_end, b_end);
+            if start <= end {
+                out.push((start, end));
+            }
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        RangeSet { ranges: out }
+    }
+
+    /// Everything in this set which is not in `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        match (self.min(), self.max()) {
+            (Some(min), Some(max)) => self.intersection(&other.complement(min..=max)),
+            _ => Self::new(),
+        }
+    }
+
+    /// Everything within `bounds` which is not in this set
+    pub fn complement(&self, bounds: RangeInclusive<T>) -> Self {
+        let (low, high) = bounds.into_inner();
+        let mut out = vec![];
+        let mut next = Some(low);
+        for (start, end) in self.ranges.iter().copied() {
+            let Some(gap_start) = next else {
+                break;
+            };
+            if start > gap_start {
+                out.push((gap_start, std::cmp::min(start - T::one(), high)));
+            }
+            next = end
+                .checked_add(&T::one())
+                .map(|n| std::cmp::max(n, gap_start));
+        }
+        if let Some(gap_start) = next {
+            out.push((gap_start, high));
+        }
+        Self::normalised(out)
+    }
+}
+
+/// The number of integers from `start` to `end` inclusive, worked out in 128
+/// bits so that ranges as wide as the whole type do not overflow. None if even
+/// that is too small, which only happens for the full range of a 128-bit type.
+fn span<T: PrimInt>(start: T, end: T) -> Option<u128> {
+    let gap = match (start.to_i128(), end.to_i128()) {
+        (Some(start), Some(end)) => end.abs_diff(start),
+        _ => end.to_u128()? - start.to_u128()?,
+    };
+    gap.checked_add(1)
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for RangeSet<T> {
+    fn from_iter<It: IntoIterator<Item = RangeInclusive<T>>>(iter: It) -> Self {
+        Self::normalised(iter.into_iter().map(|r| r.into_inner()).collect())
+    }
+}
+
+impl<T: PrimInt> Extend<RangeInclusive<T>> for RangeSet<T> {
+    fn extend<It: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: It) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.extend(iter.into_iter().map(|r| r.into_inner()));
+        *self = Self::normalised(ranges);
+    }
+}
+
+impl<T: PrimInt + fmt::Display> fmt::Display for RangeSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, (start, end)) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            if start == end {
+                write!(f, "{}", start)?;
+            } else {
+                write!(f, "{}..={}", start, end)?;
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RangeSet;
+    use proptest::prelude::*;
+    use std::collections::BTreeSet;
+    use std::ops::RangeInclusive;
+
+    #[test]
+    fn test_normalise() {
+        let empty = RangeInclusive::new(20, 19);
+        let set = [5..=8, 1..=2, 3..=3, 10..=12, 7..=9, empty]
+            .into_iter()
+            .collect::<RangeSet<i32>>();
+        assert_eq!(set.ranges().collect::<Vec<_>>(), vec![1..=3, 5..=12]);
+        assert_eq!(set.len(), Some(11));
+        assert_eq!(set.to_string(), "[1..=3, 5..=12]");
+        assert!(set.contains(3));
+        assert!(!set.contains(4));
+        assert!(set.contains(12));
+        assert!(!set.contains(13));
+    }
+
+    #[test]
+    fn test_algebra() {
+        let a = RangeSet::from_range(1..=4000);
+        let b = [1000..=1999, 3000..=3500]
+            .into_iter()
+            .collect::<RangeSet<i32>>();
+        assert_eq!(a.intersection(&b), b);
+        assert_eq!(
+            a.difference(&b).ranges().collect::<Vec<_>>(),
+            vec![1..=999, 2000..=2999, 3501..=4000]
+        );
+        assert_eq!(b.complement(1..=4000), a.difference(&b));
+        assert_eq!(a.union(&b), a);
+        let mut c = a.clone();
+        c.remove(2..=3999);
+        assert_eq!(c.to_string(), "[1, 4000]");
+    }
+
+    #[test]
+    fn test_extremes() {
+        let full = RangeSet::from_range(u8::MIN..=u8::MAX);
+        assert_eq!(full.len(), Some(256));
+        assert!(full.complement(0..=255).is_empty());
+        let top = RangeSet::from_range(250u8..=255);
+        assert_eq!(top.complement(0..=255), RangeSet::from_range(0..=249));
+        let mut joined = RangeSet::from_range(i64::MIN..=-1);
+        joined.insert(0..=i64::MAX);
+        assert_eq!(joined.ranges().count(), 1);
+    }
+
+    #[test]
+    fn test_len_extremes() {
+        // these assume a 64-bit usize
+        assert_eq!(
+            RangeSet::from_range(i32::MIN..=i32::MAX).len(),
+            Some(1 << 32)
+        );
+        assert_eq!(RangeSet::from_range(i64::MIN..=i64::MAX).len(), None);
+        assert_eq!(
+            RangeSet::from_range(i64::MIN..=i64::MAX - 1).len(),
+            Some(usize::MAX)
+        );
+        assert_eq!(RangeSet::from_range(1..=u64::MAX).len(), Some(usize::MAX));
+        assert_eq!(RangeSet::from_range(0..=u64::MAX).len(), None);
+        assert_eq!(RangeSet::from_range(i128::MIN..=i128::MAX).len(), None);
+        assert_eq!(
+            RangeSet::from_range(u128::MAX - 4..=u128::MAX).len(),
+            Some(5)
+        );
+        // each range fits, but not the total
+        let mut halves = RangeSet::from_range(0..=1i128 << 63);
+        halves.insert((1 << 63) + 2..=(1 << 64) + 1);
+        assert_eq!(halves.ranges().count(), 2);
+        assert_eq!(halves.len(), None);
+    }
+
+    fn brute(ranges: &[(i8, i8)]) -> BTreeSet<i8> {
+        ranges
+            .iter()
+            .flat_map(|(start, end)| *start..=*end)
+            .collect()
+    }
+
+    fn build(ranges: &[(i8, i8)]) -> RangeSet<i8> {
+        ranges.iter().map(|(start, end)| *start..=*end).collect()
+    }
+
+    fn ranges() -> impl Strategy<Value = Vec<(i8, i8)>> {
+        prop::collection::vec((-20i8..20, 0i8..8).prop_map(|(s, l)| (s, s + l)), 0..6)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_matches_brute_force(a in ranges(), b in ranges(), lo in -25i8..0, hi in 0i8..25) {
+            let (set_a, set_b) = (build(&a), build(&b));
+            let (brute_a, brute_b) = (brute(&a), brute(&b));
+            prop_assert_eq!(set_a.iter().collect::<BTreeSet<_>>(), brute_a.clone());
+            prop_assert_eq!(set_a.len(), Some(brute_a.len()));
+            prop_assert_eq!(
+                set_a.union(&set_b).iter().collect::<BTreeSet<_>>(),
+                brute_a.union(&brute_b).copied().collect::<BTreeSet<_>>()
+            );
+            prop_assert_eq!(
+                set_a.intersection(&set_b).iter().collect::<BTreeSet<_>>(),
+                brute_a.intersection(&brute_b).copied().collect::<BTreeSet<_>>()
+            );
+            prop_assert_eq!(
+                set_a.difference(&set_b).iter().collect::<BTreeSet<_>>(),
+                brute_a.difference(&brute_b).copied().collect::<BTreeSet<_>>()
+            );
+            prop_assert_eq!(
+                set_a.complement(lo..=hi).iter().collect::<BTreeSet<_>>(),
+                (lo..=hi).filter(|v| !brute_a.contains(v)).collect::<BTreeSet<_>>()
+            );
+            for v in -30i8..30 {
+                prop_assert_eq!(set_a.contains(v), brute_a.contains(&v));
+            }
+            // the normal form is unique, so equal sets have equal ranges
+            let rebuilt = brute_a.iter().map(|v| *v..=*v).collect::<RangeSet<_>>();
+            prop_assert_eq!(rebuilt, set_a);
+        }
+    }
+}
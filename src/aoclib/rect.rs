@@ -0,0 +1,190 @@
+use num::iter::range_inclusive;
+use num_traits::PrimInt;
+
+use super::dimval::DimVal;
+use super::point::Point;
+
+/// An axis-aligned rectangle. Both corners are inclusive, so a rectangle whose
+/// corners are the same point still covers that one point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect<I: DimVal = i64> {
+    pub min: Point<I>,
+    pub max: Point<I>,
+}
+
+impl<I: DimVal> Rect<I> {
+    /// Build the rectangle spanning two opposite corners, given in any order
+    pub fn new(corner: Point<I>, opposite_corner: Point<I>) -> Self {
+        Rect {
+            min: corner.componentwise_min(opposite_corner),
+            max: corner.componentwise_max(opposite_corner),
+        }
+    }
+
+    /// The smallest rectangle containing every point, or None if there are none
+    pub fn bounding<P: IntoIterator<Item = Point<I>>>(points: P) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Rect::new(first, first), |rect, p| {
+            rect.union(&Rect::new(p, p))
+        }))
+    }
+
+    pub fn contains(&self, p: Point<I>) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    /// Whether `other` lies entirely inside this rectangle
+    pub fn contains_rect(&self, other: &Rect<I>) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    /// The overlap between two rectangles, or None if they do not touch
+    pub fn intersection(&self, other: &Rect<I>) -> Option<Self> {
+        let min = self.min.componentwise_max(other.min);
+        let max = self.max.componentwise_min(other.max);
+        if min.x > max.x || min.y > max.y {
+            None
+        } else {
+            Some(Rect { min, max })
+        }
+    }
+
+    /// The smallest rectangle containing both rectangles
+    pub fn union(&self, other: &Rect<I>) -> Self {
+        Rect {
+            min: self.min.componentwise_min(other.min),
+            max: self.max.componentwise_max(other.max),
+        }
+    }
+
+    /// Grow the rectangle by `amount` on every side. Panics if `amount` is negative.
+    pub fn expand(&self, amount: I) -> Self {
+        assert!(!amount.is_negative(), "cannot expand by a negative amount");
+        let offset = Point::new(amount, amount);
+        Rect {
+            min: self.min - offset,
+            max: self.max + offset,
+        }
+    }
+
+    pub fn top_left(&self) -> Point<I> {
+        self.min
+    }
+
+    pub fn top_right(&self) -> Point<I> {
+        Point::new(self.max.x, self.min.y)
+    }
+
+    pub fn bottom_left(&self) -> Point<I> {
+        Point::new(self.min.x, self.max.y)
+    }
+
+    pub fn bottom_right(&self) -> Point<I> {
+        self.max
+    }
+
+    /// All four corners, clockwise from the top left
+    pub fn corners(&self) -> [Point<I>; 4] {
+        [
+            self.top_left(),
+            self.top_right(),
+            self.bottom_right(),
+            self.bottom_left(),
+        ]
+    }
+}
+
+impl<I: DimVal + PrimInt> Rect<I> {
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x).to_usize().unwrap() + 1
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y).to_usize().unwrap() + 1
+    }
+
+    /// The number of points covered
+    pub fn area(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    /// Every point in the rectangle, in the same row-major order as
+    /// [`crate::DenseGrid::iter`]
+    pub fn points(&self) -> impl Iterator<Item = Point<I>> {
+        let (min_x, max_x) = (self.min.x, self.max.x);
+        range_inclusive(self.min.y, self.max.y)
+            .flat_map(move |y| range_inclusive(min_x, max_x).map(move |x| Point::new(x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rect;
+    use crate::Point;
+
+    #[test]
+    fn test_basic() {
+        let r = Rect::new(Point::new(3, -1), Point::new(0, 2));
+        assert_eq!(r.min, Point::new(0, -1));
+        assert_eq!(r.max, Point::new(3, 2));
+        assert_eq!((r.width(), r.height(), r.area()), (4, 4, 16));
+        assert!(r.contains(Point::new(3, -1)));
+        assert!(!r.contains(Point::new(4, 0)));
+        assert_eq!(
+            r.corners(),
+            [
+                Point::new(0, -1),
+                Point::new(3, -1),
+                Point::new(3, 2),
+                Point::new(0, 2)
+            ]
+        );
+        let points = r.points().collect::<Vec<_>>();
+        assert_eq!(points.len(), 16);
+        assert_eq!(points[1], Point::new(1, -1));
+        assert_eq!(points[15], Point::new(3, 2));
+
+        let single = Rect::new(Point::new(5, 5), Point::new(5, 5));
+        assert_eq!(single.area(), 1);
+        assert_eq!(single.points().collect::<Vec<_>>(), vec![Point::new(5, 5)]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = Rect::new(Point::new(0, 0), Point::new(4, 4));
+        let b = Rect::new(Point::new(3, 2), Point::new(6, 8));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Rect::new(Point::new(3, 2), Point::new(4, 4)))
+        );
+        assert_eq!(a.union(&b), Rect::new(Point::new(0, 0), Point::new(6, 8)));
+        let far = Rect::new(Point::new(5, 5), Point::new(6, 6));
+        assert_eq!(a.intersection(&far), None);
+        let touching = Rect::new(Point::new(4, 4), Point::new(9, 9));
+        assert_eq!(touching.intersection(&a).unwrap().area(), 1);
+        assert!(a.union(&b).contains_rect(&a));
+        assert!(!a.contains_rect(&b));
+        assert_eq!(
+            Rect::bounding([Point::new(2, 7), Point::new(-1, 3), Point::new(0, 9)]),
+            Some(Rect::new(Point::new(-1, 3), Point::new(2, 9)))
+        );
+        assert_eq!(Rect::<i64>::bounding([]), None);
+    }
+
+    #[test]
+    fn test_expand() {
+        let r = Rect::new(Point::new(2, 0), Point::new(4, 0));
+        let around = r.expand(1);
+        assert_eq!(around, Rect::new(Point::new(1, -1), Point::new(5, 1)));
+        assert_eq!(around.area(), 15);
+        assert_eq!(r.expand(0), r);
+    }
+
+    #[test]
+    fn test_float() {
+        let area = Rect::new(Point::new(7.0, 7.0), Point::new(27.0, 27.0));
+        assert!(area.contains(Point::new(14.333, 15.333)));
+        assert!(!area.contains(Point::new(-2.0, 3.0)));
+    }
+}
@@ -1,27 +1,172 @@
-use crate::dimval::DimVal;
+use crate::dimval::{greater, lesser, manhattan_distance, DimVal, Widen};
+use crate::point::Point;
+use num_traits::{CheckedAdd, CheckedNeg, PrimInt, SaturatingMul};
 use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, Hash, PartialOrd)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Vec3<T: DimVal = i64> {
     pub x: T,
     pub y: T,
     pub z: T,
 }
-impl<I: DimVal + Eq> Eq for Vec3<I> {}
-
-impl<I: DimVal + Ord> Ord for Vec3<I> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
-    }
-}
 
 impl<T: DimVal> Vec3<T> {
     pub fn new(x: T, y: T, z: T) -> Self {
         Self { x, y, z }
     }
+
+    /// Drop the z coordinate
+    pub fn xy(&self) -> Point<T> {
+        Point::new(self.x, self.y)
+    }
+
+    /// The taxicab length of this vector. Panics if it does not fit in a usize;
+    /// see [`Vec3::checked_manhattan`]
+    pub fn manhattan(&self) -> usize {
+        self.checked_manhattan()
+            .expect("manhattan length overflows usize")
+    }
+
+    /// The taxicab length, or None if it does not fit in a usize
+    pub fn checked_manhattan(&self) -> Option<usize> {
+        self.checked_manhattan_distance_to(Vec3::new(T::zero(), T::zero(), T::zero()))
+    }
+
+    /// Panics if the distance does not fit in a usize; see
+    /// [`Vec3::checked_manhattan_distance_to`]
+    pub fn manhattan_distance_to(&self, other: Vec3<T>) -> usize {
+        self.checked_manhattan_distance_to(other)
+            .expect("manhattan distance overflows usize")
+    }
+
+    /// The manhattan distance, or None if it does not fit in a usize. This never
+    /// overflows along the way, even for points at opposite ends of `i128`.
+    pub fn checked_manhattan_distance_to(&self, other: Vec3<T>) -> Option<usize> {
+        manhattan_distance([(self.x, other.x), (self.y, other.y), (self.z, other.z)])
+    }
+
+    pub fn abs(&self) -> Self {
+        Vec3::new(self.x.abs(), self.y.abs(), self.z.abs())
+    }
+
+    /// Each component replaced by -1, 0 or 1 according to its sign
+    pub fn signum(&self) -> Self {
+        Vec3::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+
+    /// The smaller of each pair of components
+    pub fn componentwise_min(&self, other: Vec3<T>) -> Self {
+        Vec3::new(
+            lesser(self.x, other.x),
+            lesser(self.y, other.y),
+            lesser(self.z, other.z),
+        )
+    }
+
+    /// The larger of each pair of components
+    pub fn componentwise_max(&self, other: Vec3<T>) -> Self {
+        Vec3::new(
+            greater(self.x, other.x),
+            greater(self.y, other.y),
+            greater(self.z, other.z),
+        )
+    }
+}
+
+impl<T: Widen> Vec3<T> {
+    /// Convert each component to the wider type used for products
+    pub fn widen(&self) -> Vec3<T::Wide> {
+        Vec3::new(self.x.into(), self.y.into(), self.z.into())
+    }
+
+    /// The dot product, computed in the wider type. Panics if even that
+    /// overflows; see [`Vec3::checked_dot`]
+    pub fn dot(&self, other: Vec3<T>) -> T::Wide {
+        self.checked_dot(other)
+            .expect("dot product overflows the wide type")
+    }
+
+    /// The dot product, or None if the sum of the products does not fit in the
+    /// wider type
+    pub fn checked_dot(&self, other: Vec3<T>) -> Option<T::Wide> {
+        let (a, b) = (self.widen(), other.widen());
+        (a.x * b.x)
+            .checked_add(&(a.y * b.y))?
+            .checked_add(&(a.z * b.z))
+    }
+
+    /// The cross product, computed in the wider type. Each component is the
+    /// difference of two products, so it is always exact.
+    pub fn cross(&self, other: Vec3<T>) -> Vec3<T::Wide> {
+        let (a, b) = (self.widen(), other.widen());
+        Vec3::new(
+            a.y * b.z - a.z * b.y,
+            a.z * b.x - a.x * b.z,
+            a.x * b.y - a.y * b.x,
+        )
+    }
 }
 
-impl<T: DimVal + std::ops::Add> std::ops::Add for Vec3<T> {
+/// Arithmetic which reports or clamps overflow rather than wrapping
+impl<T: DimVal + PrimInt + CheckedNeg + SaturatingMul> Vec3<T> {
+    pub fn checked_add(&self, other: Vec3<T>) -> Option<Self> {
+        Some(Vec3::new(
+            self.x.checked_add(&other.x)?,
+            self.y.checked_add(&other.y)?,
+            self.z.checked_add(&other.z)?,
+        ))
+    }
+
+    pub fn checked_sub(&self, other: Vec3<T>) -> Option<Self> {
+        Some(Vec3::new(
+            self.x.checked_sub(&other.x)?,
+            self.y.checked_sub(&other.y)?,
+            self.z.checked_sub(&other.z)?,
+        ))
+    }
+
+    pub fn checked_mul(&self, scalar: T) -> Option<Self> {
+        Some(Vec3::new(
+            self.x.checked_mul(&scalar)?,
+            self.y.checked_mul(&scalar)?,
+            self.z.checked_mul(&scalar)?,
+        ))
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Some(Vec3::new(
+            self.x.checked_neg()?,
+            self.y.checked_neg()?,
+            self.z.checked_neg()?,
+        ))
+    }
+
+    pub fn saturating_add(&self, other: Vec3<T>) -> Self {
+        Vec3::new(
+            self.x.saturating_add(other.x),
+            self.y.saturating_add(other.y),
+            self.z.saturating_add(other.z),
+        )
+    }
+
+    pub fn saturating_sub(&self, other: Vec3<T>) -> Self {
+        Vec3::new(
+            self.x.saturating_sub(other.x),
+            self.y.saturating_sub(other.y),
+            self.z.saturating_sub(other.z),
+        )
+    }
+
+    pub fn saturating_mul(&self, scalar: T) -> Self {
+        Vec3::new(
+            self.x.saturating_mul(&scalar),
+            self.y.saturating_mul(&scalar),
+            self.z.saturating_mul(&scalar),
+        )
+    }
+}
+
+impl<T: DimVal> std::ops::Add for Vec3<T> {
     type Output = Vec3<T>;
 
     fn add(self, rhs: Vec3<T>) -> Self::Output {
@@ -33,15 +178,13 @@ impl<T: DimVal + std::ops::Add> std::ops::Add for Vec3<T> {
     }
 }
 
-impl<T: DimVal + std::ops::AddAssign> std::ops::AddAssign for Vec3<T> {
+impl<T: DimVal> std::ops::AddAssign for Vec3<T> {
     fn add_assign(&mut self, rhs: Self) {
-        self.x += rhs.x;
-        self.y += rhs.y;
-        self.z += rhs.z
+        *self = *self + rhs;
     }
 }
 
-impl<T: DimVal + std::ops::Sub> std::ops::Sub for Vec3<T> {
+impl<T: DimVal> std::ops::Sub for Vec3<T> {
     type Output = Vec3<T>;
 
     fn sub(self, rhs: Vec3<T>) -> Self::Output {
@@ -53,10 +196,24 @@ impl<T: DimVal + std::ops::Sub> std::ops::Sub for Vec3<T> {
     }
 }
 
-impl<T: DimVal + std::ops::Mul<i64, Output = T>> std::ops::Mul<i64> for Vec3<T> {
+impl<T: DimVal> std::ops::SubAssign for Vec3<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: DimVal> std::ops::Neg for Vec3<T> {
     type Output = Vec3<T>;
 
-    fn mul(self, scalar: i64) -> Self::Output {
+    fn neg(self) -> Self::Output {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: DimVal> std::ops::Mul<T> for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn mul(self, scalar: T) -> Self::Output {
         Self::Output {
             x: self.x * scalar,
             y: self.y * scalar,
@@ -65,8 +222,121 @@ impl<T: DimVal + std::ops::Mul<i64, Output = T>> std::ops::Mul<i64> for Vec3<T>
     }
 }
 
-impl fmt::Display for Vec3 {
+/// Divide every component by a scalar. For integers this rounds towards zero.
+impl<T: DimVal> std::ops::Div<T> for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn div(self, scalar: T) -> Self::Output {
+        Vec3::new(self.x / scalar, self.y / scalar, self.z / scalar)
+    }
+}
+
+impl<T: DimVal> fmt::Display for Vec3<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "({}, {}, {})", self.x, self.y, self.z)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Vec3;
+    use crate::Point;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Vec3::new(1, -2, 3);
+        let b = Vec3::new(4, 5, -6);
+        assert_eq!(a + b, Vec3::new(5, 3, -3));
+        assert_eq!(a - b, Vec3::new(-3, -7, 9));
+        assert_eq!(-a, Vec3::new(-1, 2, -3));
+        assert_eq!(a * 3, Vec3::new(3, -6, 9));
+        assert_eq!(b / 2, Vec3::new(2, 2, -3));
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+    }
+
+    #[test]
+    fn test_products() {
+        let x = Vec3::<i64>::new(1, 0, 0);
+        let y = Vec3::new(0, 1, 0);
+        assert_eq!(x.cross(y), Vec3::new(0, 0, 1));
+        assert_eq!(y.cross(x), Vec3::new(0, 0, -1));
+        let a = Vec3::new(1i32, -2, 3);
+        let b = Vec3::new(4, 5, -6);
+        assert_eq!(a.dot(b), -24);
+        assert_eq!(a.cross(b).dot(a.widen()), 0);
+        assert_eq!(a.cross(b).dot(b.widen()), 0);
+    }
+
+    #[test]
+    fn test_components() {
+        let a = Vec3::new(1, -2, 3);
+        let b = Vec3::new(4, 5, -6);
+        assert_eq!(a.manhattan(), 6);
+        assert_eq!(a.manhattan_distance_to(b), 19);
+        assert_eq!(b.abs(), Vec3::new(4, 5, 6));
+        assert_eq!(Vec3::new(-7, 0, 2).signum(), Vec3::new(-1, 0, 1));
+        assert_eq!(a.componentwise_min(b), Vec3::new(1, -2, -6));
+        assert_eq!(a.componentwise_max(b), Vec3::new(4, 5, 3));
+        assert_eq!(a.xy(), Point::new(1, -2));
+        assert_eq!(a.xy().with_z(3), a);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let big = Vec3::new(0, i64::MAX, 0);
+        assert_eq!(big.checked_add(Vec3::new(0, 1, 0)), None);
+        assert_eq!(big.checked_sub(big), Some(Vec3::new(0, 0, 0)));
+        assert_eq!(big.checked_mul(-1), Some(Vec3::new(0, -i64::MAX, 0)));
+        assert_eq!(Vec3::new(0, 0, i64::MIN).checked_neg(), None);
+        assert_eq!(big.saturating_mul(3), Vec3::new(0, i64::MAX, 0));
+        assert_eq!(
+            (-big).saturating_sub(Vec3::new(1, 2, 3)),
+            Vec3::new(-1, i64::MIN, -3)
+        );
+        // positions around 4e14 times velocities in the hundreds are fine in i128
+        let p = Vec3::<i64>::new(400_000_000_000_000, 300_000_000_000_000, 0);
+        let v = Vec3::new(-300, 400, 250);
+        assert_eq!(p.dot(v), 0);
+        assert_eq!(
+            p.cross(v),
+            Vec3::new(
+                75_000_000_000_000_000,
+                -100_000_000_000_000_000,
+                250_000_000_000_000_000
+            )
+        );
+        assert_eq!(
+            Vec3::new(i128::MAX, 0, 0).checked_manhattan_distance_to(Vec3::new(-1, 0, 0)),
+            None
+        );
+
+        // three products near MIN * MIN add up to more than i128 can hold, but
+        // the cross product only ever subtracts them
+        let corner = Vec3::new(i64::MIN, i64::MIN, i64::MIN);
+        assert_eq!(corner.checked_dot(corner), None);
+        let big = i64::MIN as i128 * i64::MIN as i128;
+        assert_eq!(
+            corner.checked_dot(Vec3::new(i64::MIN, 0, 1)),
+            Some(big + i64::MIN as i128)
+        );
+        assert_eq!(
+            corner.cross(Vec3::new(i64::MAX, i64::MIN, i64::MAX)),
+            Vec3::new(
+                i64::MIN as i128 * i64::MAX as i128 - big,
+                0,
+                big - i64::MIN as i128 * i64::MAX as i128,
+            )
+        );
+        assert_eq!(corner.checked_manhattan(), None);
+        assert_eq!(Vec3::new(i32::MIN, i32::MIN, i32::MIN).manhattan(), 3 << 31);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Vec3::new(1, -2, 3).to_string(), "(1, -2, 3)");
+        assert_eq!(Vec3::new(0.5, 1.0, -2.5).to_string(), "(0.5, 1, -2.5)");
+    }
+}
@@ -1,28 +1,7 @@
-use aoclib::Point;
+use aoclib::{polygon, DenseGrid, Direction, Point};
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Direction {
-    North,
-    West,
-    South,
-    East,
-}
-
-impl Direction {
-    fn invert(&self) -> Direction {
-        use Direction::*;
-
-        match self {
-            North => South,
-            West => East,
-            South => North,
-            East => West,
-        }
-    }
-}
-
-#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
 enum Tile {
     NorthSouth,
     EastWest,
@@ -30,6 +9,7 @@ enum Tile {
     NorthWest,
     SouthWest,
     SouthEast,
+    #[default]
     Ground,
     Start,
 }
@@ -89,176 +69,54 @@ impl Tile {
     }
 }
 
-struct PassabilityMap {
-    inner: BTreeMap<Point<i32>, bool>,
-    width: i32,
-    height: i32,
-}
-
-impl PassabilityMap {
-    fn from(m: &Map, loop_points: &[Point<i32>]) -> Self {
-        // explode a regular map into a map that shows how flows can pass *between* pipes
-        //
-        // AB
-        // CD
-        //
-        // goes to
-        //
-        // A?B
-        // ?.?
-        // C?D
-        //
-        // the ?'s can be determined by looking at adjacent cells. Remmber, pipes only count
-        // if they're on the "main" loop; even if we have other smaller loops, we ignore them
-        let mut out = BTreeMap::new();
-        let mut width = 0;
-        let mut height = 0;
-        for y in 0..=m.height {
-            for x in 0..=m.width {
-                let point = Point::new(x, y);
-                let new_point = Point::new(x * 2, y * 2);
-                width = std::cmp::max(width, x * 2);
-                let tile = if loop_points.contains(&point) {
-                    m.tiles[&point]
-                } else {
-                    Tile::Ground
-                };
-                out.insert(new_point, tile == Tile::Ground);
-                let right_point = point + Point::new(1, 0);
-                let below_point = point + Point::new(0, 1);
-                let right = if loop_points.contains(&right_point) {
-                    m.tiles.get(&right_point).cloned()
-                } else {
-                    Some(Tile::Ground)
-                };
-                let below = if loop_points.contains(&below_point) {
-                    m.tiles.get(&below_point).cloned()
-                } else {
-                    Some(Tile::Ground)
-                };
-                let tc = match (tile, right) {
-                    (Tile::Ground, _) => true,
-                    (_, None) => true,
-                    (l, Some(r))
-                        if l.connects_from(Direction::East) && r.connects_from(Direction::West) =>
-                    {
-                        false
-                    }
-                    _ => true,
-                };
-                out.insert(new_point + Point::new(1, 0), tc);
-                let lm = match (tile, below) {
-                    (Tile::Ground, _) => true,
-                    (_, None) => true,
-                    (l, Some(r))
-                        if l.connects_from(Direction::South)
-                            && r.connects_from(Direction::North) =>
-                    {
-                        false
-                    }
-                    _ => true,
-                };
-                out.insert(new_point + Point::new(0, 1), lm);
-                let new_right_below = new_point + Point::new(1, 1);
-                out.insert(new_right_below, true);
-            }
-            height = std::cmp::max(height, y * 2);
-        }
-        Self {
-            inner: out,
-            width,
-            height,
-        }
-    }
-
-    fn dump(&self) {
-        for y in 0..=self.height {
-            for x in 0..=self.width {
-                let point = Point::new(x, y);
-                let c = if *self.inner.get(&point).unwrap() {
-                    '.'
-                } else {
-                    'x'
-                };
-                print!("{}", c);
-            }
-            println!()
-        }
-    }
-}
-
 #[derive(Debug)]
 struct Map {
-    tiles: BTreeMap<Point<i32>, Tile>,
+    tiles: DenseGrid<Tile, i32>,
     adjacencies: BTreeMap<Point<i32>, BTreeSet<Point<i32>>>,
-    width: i32,
-    height: i32,
 }
 
 impl Map {
     fn from_input(input: &str) -> Self {
-        let mut tiles = BTreeMap::new();
-        let mut width = 0;
-        let mut height = 0;
-        for (y, row) in input.lines().enumerate() {
-            for (x, chr) in row.chars().enumerate() {
-                let coord = Point::<i32>::new(x as i32, y as i32);
-                let tile = Tile::from_char(chr);
-                tiles.insert(coord, tile);
-                width = std::cmp::max(width, x);
-            }
-            height = std::cmp::max(height, y);
-        }
+        let tiles = DenseGrid::from_input(input, Tile::from_char)
+            .cast_index::<i32>()
+            .unwrap();
         let mut adjacencies = BTreeMap::new();
         for (point, tile) in tiles.iter() {
             for direction in tile.connects_to() {
-                let that_way = match direction {
-                    Direction::North => Point::new(point.x, point.y - 1),
-                    Direction::West => Point::new(point.x - 1, point.y),
-                    Direction::South => Point::new(point.x, point.y + 1),
-                    Direction::East => Point::new(point.x + 1, point.y),
-                };
-                if that_way.x < 0 || that_way.y < 0 {
+                let that_way = point + direction.as_point();
+                if !tiles.contains(that_way) {
                     continue;
                 }
-                if that_way.x > (width as i32) || that_way.y > (height as i32) {
-                    continue;
-                }
-                if tiles[&that_way].connects_from(direction.invert()) {
+                if tiles[that_way].connects_from(direction.opposite()) {
                     adjacencies
-                        .entry(*point)
+                        .entry(point)
                         .or_insert_with(BTreeSet::new)
                         .insert(that_way);
                     adjacencies
                         .entry(that_way)
                         .or_insert_with(BTreeSet::new)
-                        .insert(*point);
+                        .insert(point);
                 }
             }
         }
-        Map {
-            tiles,
-            adjacencies,
-            width: width as i32,
-            height: height as i32,
-        }
+        Map { tiles, adjacencies }
     }
 
     fn loop_containing_start(&self) -> Option<Vec<Point<i32>>> {
         let start = self
             .tiles
             .iter()
-            .find(|(_, t)| **t == Tile::Start)
+            .find(|(_, t)| *t == Tile::Start)
             .unwrap()
             .0;
         let mut queue = VecDeque::new();
-        queue.push_front((start, vec![]));
+        queue.push_front((&start, vec![]));
         while let Some((node, path)) = queue.pop_back() {
             let mut next = path.clone();
             next.push(*node);
             if let Some(neighbors) = self.adjacencies.get(node) {
                 for neighbor in neighbors {
-                    if neighbor == start && path.len() > 1 {
+                    if *neighbor == start && path.len() > 1 {
                         return Some(next);
                     }
                     if path.contains(neighbor) {
@@ -272,94 +130,11 @@ impl Map {
     }
 
     fn tiles_contained(&self, loop_points: &[Point<i32>]) -> usize {
-        let exploded = PassabilityMap::from(self, loop_points);
-        if false {
-            exploded.dump();
-        }
-
-        // for every tile, either it has a path to the edge, all paths lead
-        // to the loop, or it's contained in some other loop. Or it is this this loop, lol.
-        let mut path_to_edge = BTreeSet::new();
-        let mut exploded_path_to_edge = BTreeSet::new();
-        let mut exploded_contained = BTreeSet::new();
-        let mut path_to_loop = BTreeSet::new();
-        let mut contained: BTreeSet<Point<i32>> = BTreeSet::new();
-        for (point, _tile) in self.tiles.iter() {
-            //println!("examining {:?}", point);
-            if loop_points.contains(point) {
-                path_to_loop.insert(*point);
-            } else if point.x == 0
-                || point.y == 0
-                || point.x == self.width
-                || point.y == self.height
-            {
-                path_to_edge.insert(*point);
-                exploded_path_to_edge.insert(Point::new(point.x * 2, point.y * 2));
-                exploded_path_to_edge.insert(Point::new(point.x * 2 + 1, point.y * 2));
-                exploded_path_to_edge.insert(Point::new(point.x * 2, point.y * 2 + 1));
-                exploded_path_to_edge.insert(Point::new(point.x * 2 + 1, point.y * 2 + 1));
-            } else {
-                // bfs out in the exploded map
-                let mut queue: VecDeque<Point<i32>> = VecDeque::new();
-                let mut seen: BTreeSet<Point<i32>> = BTreeSet::new();
-                let mut escaped = false;
-                // can start at any of the 4 points that this point maps to
-                queue.push_back(Point::new(point.x * 2, point.y * 2));
-                queue.push_back(Point::new(point.x * 2 + 1, point.y * 2));
-                queue.push_back(Point::new(point.x * 2, point.y * 2 + 1));
-                queue.push_back(Point::new(point.x * 2 + 1, point.y * 2 + 1));
-                while let Some(new_point) = queue.pop_front() {
-                    if seen.contains(&new_point) {
-                        continue;
-                    }
-                    seen.insert(new_point);
-                    if let Some(false) = exploded.inner.get(&new_point) {
-                        continue;
-                    }
-                    if exploded_path_to_edge.contains(&new_point)
-                        || new_point.x == 0
-                        || new_point.y == 0
-                        || new_point.x == exploded.width
-                        || new_point.y == exploded.height
-                    {
-                        exploded_path_to_edge.insert(new_point);
-                        escaped = true;
-                        break;
-                    } else if exploded_contained.contains(&new_point) {
-                        break;
-                    } else {
-                        queue.push_back(new_point + Point::new(0, 1));
-                        queue.push_back(new_point + Point::new(1, 0));
-                        queue.push_back(new_point + Point::new(-1, 0));
-                        queue.push_back(new_point + Point::new(0, -1));
-                    }
-                }
-                if !escaped {
-                    exploded_contained.insert(Point::new(point.x * 2, point.y * 2));
-                    exploded_contained.insert(Point::new(point.x * 2 + 1, point.y * 2));
-                    exploded_contained.insert(Point::new(point.x * 2, point.y * 2 + 1));
-                    exploded_contained.insert(Point::new(point.x * 2 + 1, point.y * 2 + 1));
-                    contained.insert(*point);
-                }
-            }
-        }
-        if false {
-            println!();
-            for y in 0..=self.height {
-                for x in 0..=self.width {
-                    let point = Point::new(x, y);
-                    if loop_points.contains(&point) {
-                        print!(".");
-                    } else if contained.contains(&point) {
-                        print!("I");
-                    } else {
-                        print!("O");
-                    }
-                }
-                println!();
-            }
-        }
-        contained.len()
+        let vertices = loop_points
+            .iter()
+            .map(|p| Point::new(p.x as i64, p.y as i64))
+            .collect::<Vec<_>>();
+        polygon::interior_points(&vertices) as usize
     }
 }
 
@@ -1,8 +1,6 @@
-use std::collections::BTreeMap;
+use aoclib::memo::Memo;
 
-const MAX_GROUPS: usize = 32;
-
-type GroupType = u8;
+type GroupType = usize;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 enum Spring {
@@ -22,46 +20,15 @@ impl Spring {
     }
 }
 
-fn s2i(ss: &[Spring]) -> u128 {
-    let mut i = 0;
-    for s in ss {
-        if *s == Spring::Broken {
-            i += 1;
-        }
-        i <<= 1;
-    }
-    i
-}
-
 #[derive(Debug, Clone)]
 struct Row {
     springs: Vec<Spring>,
     groups: Vec<GroupType>,
 }
 
-#[derive(Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
-struct CacheKey {
-    current_broken: GroupType,
-    springs_len: usize,
-    springs: u128,
-    groups: [GroupType; MAX_GROUPS],
-}
-
-impl CacheKey {
-    fn new(s: &[Spring], g: &[GroupType], current_broken: GroupType) -> Self {
-        assert!(g.len() < MAX_GROUPS);
-        let mut groups = [0; MAX_GROUPS];
-        for (i, gp) in g.iter().enumerate() {
-            groups[i] = *gp;
-        }
-        CacheKey {
-            springs: s2i(s),
-            springs_len: s.len(),
-            groups,
-            current_broken,
-        }
-    }
-}
+/// How far into the springs and groups the count has got, and how long the
+/// run of broken springs just before that point is
+type CacheKey = (usize, usize, GroupType);
 
 impl Row {
     fn from_line(s: &str) -> Self {
@@ -76,58 +43,63 @@ impl Row {
 
     fn solutions_inner(
         &self,
-        dp: &mut BTreeMap<CacheKey, usize>,
-        springs: &[Spring],
-        groups: &[GroupType],
+        memo: &mut Memo<CacheKey, usize>,
+        spring: usize,
+        group: usize,
         current_broken: GroupType,
     ) -> usize {
-        let key = CacheKey::new(springs, groups, current_broken);
-        if let Some(found) = dp.get(&key) {
-            return *found;
-        };
-
-        let val = match springs.split_first() {
-            Some((Spring::Working, rs)) => {
-                if current_broken == 0 {
-                    self.solutions_inner(dp, rs, groups, 0)
-                } else {
-                    match groups {
-                        [g, rg @ ..] if *g == current_broken => self.solutions_inner(dp, rs, rg, 0),
-                        _ => 0,
-                    }
-                }
-            }
-            Some((Spring::Broken, rs)) => {
-                return match groups {
-                    [g, ..] if current_broken > *g => 0,
-                    [] => 0,
-                    _ => self.solutions_inner(dp, rs, groups, current_broken + 1),
+        memo.get_or_insert_with((spring, group, current_broken), |memo| {
+            match self.springs.get(spring) {
+                Some(Spring::Working) => self.if_working(memo, spring, group, current_broken),
+                Some(Spring::Broken) => self.if_broken(memo, spring, group, current_broken),
+                Some(Spring::Unknown) => {
+                    self.if_working(memo, spring, group, current_broken)
+                        + self.if_broken(memo, spring, group, current_broken)
                 }
+                None => match &self.groups[group..] {
+                    [] if current_broken == 0 => 1,
+                    [g] if *g == current_broken => 1,
+                    _ => 0,
+                },
             }
-            Some((Spring::Unknown, _)) => {
-                let mut new_working = springs.to_vec();
-                new_working[0] = Spring::Working;
-                let working_count = self.solutions_inner(dp, &new_working, groups, current_broken);
-                let mut new_broken = springs.to_vec();
-                new_broken[0] = Spring::Broken;
-                let broken_count = self.solutions_inner(dp, &new_broken, groups, current_broken);
-                working_count + broken_count
-            }
-            None => match groups {
-                [] if current_broken == 0 => 1,
-                [g] if *g == current_broken => 1,
-                _ => 0,
-            },
-        };
+        })
+    }
 
-        dp.insert(key, val);
+    /// The count if the spring at `spring` is working
+    fn if_working(
+        &self,
+        memo: &mut Memo<CacheKey, usize>,
+        spring: usize,
+        group: usize,
+        current_broken: GroupType,
+    ) -> usize {
+        if current_broken == 0 {
+            self.solutions_inner(memo, spring + 1, group, 0)
+        } else if self.groups.get(group) == Some(&current_broken) {
+            self.solutions_inner(memo, spring + 1, group + 1, 0)
+        } else {
+            0
+        }
+    }
 
-        val
+    /// The count if the spring at `spring` is broken
+    fn if_broken(
+        &self,
+        memo: &mut Memo<CacheKey, usize>,
+        spring: usize,
+        group: usize,
+        current_broken: GroupType,
+    ) -> usize {
+        match self.groups.get(group) {
+            Some(g) if current_broken < *g => {
+                self.solutions_inner(memo, spring + 1, group, current_broken + 1)
+            }
+            _ => 0,
+        }
     }
 
     fn num_solutions(&self) -> usize {
-        let mut cache = BTreeMap::new();
-        self.solutions_inner(&mut cache, &self.springs, &self.groups, 0)
+        self.solutions_inner(&mut Memo::new(), 0, 0, 0)
     }
 
     fn to_part2(&self) -> Self {
@@ -1,4 +1,4 @@
-use aoclib::DenseGrid;
+use aoclib::{DenseGrid, Ragged};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
 enum Cell {
@@ -7,6 +7,18 @@ enum Cell {
     Rock,
 }
 
+impl TryFrom<char> for Cell {
+    type Error = anyhow::Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(Cell::Ash),
+            '#' => Ok(Cell::Rock),
+            _ => anyhow::bail!("what is {:?}", c),
+        }
+    }
+}
+
 fn rc2i(rc: &[Cell]) -> u64 {
     rc.iter().fold(0, |acc, cell| {
         if *cell == Cell::Rock {
@@ -21,7 +33,7 @@ fn eq_bw(lhs: &[u64], rhs: &[u64]) -> bool {
     if lhs.len() != rhs.len() {
         return false;
     }
-    if lhs.len() == 0 {
+    if lhs.is_empty() {
         return false;
     }
     let len = lhs.len() - 1;
@@ -37,7 +49,7 @@ fn approx_eq_bw(lhs: &[u64], rhs: &[u64]) -> bool {
     if lhs.len() != rhs.len() {
         return false;
     }
-    if lhs.len() == 0 {
+    if lhs.is_empty() {
         return false;
     }
     let len = lhs.len() - 1;
@@ -93,15 +105,8 @@ fn find_smudge_reflection(vals: &[u64]) -> Option<usize> {
 fn main() -> anyhow::Result<()> {
     let stdin = std::io::stdin();
     let input = std::io::read_to_string(stdin)?;
-    let parsed = input
-        .split("\n\n")
-        .map(|segment| {
-            DenseGrid::from_input(segment, |f| match f {
-                '.' => Cell::Ash,
-                '#' => Cell::Rock,
-                _ => panic!("what is {:?}", f),
-            })
-        })
+    let parsed = DenseGrid::parse_many(&input, Ragged::Reject, Cell::try_from)?
+        .into_iter()
         .map(|grid| {
             let column_ints = grid.columns().map(|c| rc2i(&c)).collect::<Vec<u64>>();
             let row_ints = grid.rows().map(|r| rc2i(&r)).collect::<Vec<u64>>();
@@ -1,5 +1,4 @@
-use aoclib::{DenseGrid, Point};
-use std::collections::HashMap;
+use aoclib::{cycle, DenseGrid, Point};
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Default, Hash)]
 enum Cell {
@@ -11,10 +10,7 @@ enum Cell {
 
 impl Cell {
     fn passable(&self) -> bool {
-        match self {
-            Cell::Empty => true,
-            _ => false,
-        }
+        matches!(self, Cell::Empty)
     }
 
     fn from_char(c: char) -> Self {
@@ -29,29 +25,33 @@ impl Cell {
 
 fn tilt(grid: &DenseGrid<Cell>, direction: Point) -> DenseGrid<Cell> {
     let mut new = grid.clone();
-    loop {
-        let mut moved = 0;
-        for y in grid.row_numbers() {
-            for x in grid.column_numbers() {
-                let point = Point::new(x, y);
-                let value = new.get(point);
-                if let Some(Cell::Round) = value {
-                    let above = point + direction;
-                    if new.get(above).map(|x| x.passable()).unwrap_or(false) {
-                        new.set(above, Cell::Round);
-                        new.set(point, Cell::Empty);
-                        moved += 1;
-                    }
-                }
-            }
-        }
-        if moved == 0 {
-            break;
-        }
+    let mut rocks = grid
+        .iter()
+        .filter(|(_, value)| *value == Cell::Round)
+        .map(|(point, _)| point)
+        .collect::<Vec<_>>();
+    // move the rocks closest to the wall first so that the others pile up behind them
+    rocks.sort_by_key(|p| -(p.x * direction.x + p.y * direction.y));
+    for rock in rocks {
+        let (resting, _) = new.cast_until(rock, direction, |c| !c.passable());
+        new[rock] = Cell::Empty;
+        new[resting] = Cell::Round;
     }
     new
 }
 
+/// Tilt north, west, south and then east
+fn spin(grid: &DenseGrid<Cell>) -> DenseGrid<Cell> {
+    [
+        Point::new(0, -1),
+        Point::new(-1, 0),
+        Point::new(0, 1),
+        Point::new(1, 0),
+    ]
+    .into_iter()
+    .fold(grid.clone(), |g, direction| tilt(&g, direction))
+}
+
 fn score(g: &DenseGrid<Cell>) -> usize {
     let height = g.height();
     g.iter()
@@ -71,40 +71,8 @@ fn main() -> anyhow::Result<()> {
     let grid = DenseGrid::from_input(&input, Cell::from_char);
     let tilted = tilt(&grid, Point::new(0, -1));
     println!("part 1: {:?}", score(&tilted));
-    let mut g = grid.clone();
-    let mut seen = HashMap::<_, usize>::new();
-    let mut intervals = HashMap::new();
-    let mut first_repeat = None;
-    for i in 0..=500 {
-        g = tilt(&g, Point::new(0, -1));
-        g = tilt(&g, Point::new(-1, 0));
-        g = tilt(&g, Point::new(0, 1));
-        g = tilt(&g, Point::new(1, 0));
-        let hashable = g.clone();
-        if let Some(last_seen) = seen.get(&hashable) {
-            let last_seen = last_seen.clone();
-            if first_repeat.is_none() {
-                first_repeat = Some(last_seen);
-            }
-            let interval = i - last_seen;
-            *intervals.entry(interval).or_insert(0) += 1;
-        }
-        seen.insert(hashable, i);
-    }
-    let best_interval = intervals
-        .iter()
-        .max_by_key(|(_, v)| *v)
-        .map(|(k, _)| k)
-        .unwrap();
-    let first_repeat = first_repeat.unwrap();
-    let part2_target = (1_000_000_000 - first_repeat) % best_interval + first_repeat;
-    let mut g = grid;
-    for _ in 0..part2_target {
-        g = tilt(&g, Point::new(0, -1));
-        g = tilt(&g, Point::new(-1, 0));
-        g = tilt(&g, Point::new(0, 1));
-        g = tilt(&g, Point::new(1, 0));
-    }
+    let cycle = cycle::find_cycle_by_key(&grid, spin, |g| g.snapshot(|c| *c == Cell::Round));
+    let g = cycle.state_at(&grid, spin, 1_000_000_000);
     println!("part 2: {:?}", score(&g));
     Ok(())
 }
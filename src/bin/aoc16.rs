@@ -1,4 +1,4 @@
-use aoclib::{DenseGrid, Point};
+use aoclib::{DenseGrid, Point, Transform};
 use std::collections::HashSet;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
@@ -43,19 +43,6 @@ impl Beam {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum UpDown {
-    Up,
-    Down,
-}
-
-fn rotate(angle: UpDown, vector: Point) -> Point {
-    match angle {
-        UpDown::Down => Point::new(vector.y, vector.x),
-        UpDown::Up => Point::new(-1 * vector.y, -1 * vector.x),
-    }
-}
-
 fn energized(map: &DenseGrid<Tile>, beam: Beam) -> HashSet<Point> {
     let mut beams = vec![beam];
     let mut energized = HashSet::new();
@@ -76,14 +63,14 @@ fn energized(map: &DenseGrid<Tile>, beam: Beam) -> HashSet<Point> {
                 Some(Tile::MirrorDown) => {
                     beam = Beam {
                         coord: beam.coord,
-                        direction: rotate(UpDown::Down, beam.direction),
+                        direction: beam.direction.transform(Transform::ReflectDiagonal),
                     }
                     .advance();
                 }
                 Some(Tile::MirrorUp) => {
                     beam = Beam {
                         coord: beam.coord,
-                        direction: rotate(UpDown::Up, beam.direction),
+                        direction: beam.direction.transform(Transform::ReflectAntiDiagonal),
                     }
                     .advance();
                 }
@@ -24,7 +24,7 @@ fn calc(map: &DenseGrid<usize>, start: Point, destination: Point, min: usize, ma
             let mut u2 = u.0;
             let mut new_point = point;
             for j in 1..=max {
-                new_point = new_point + *new_direction;
+                new_point += *new_direction;
                 if let Some(val) = map.get(new_point) {
                     u2 += val;
                     if j >= min {
@@ -1,23 +1,4 @@
-use aoclib::Point;
-
-#[derive(Debug, PartialEq, Eq, Hash)]
-enum Direction {
-    Right,
-    Left,
-    Up,
-    Down,
-}
-
-impl Direction {
-    fn as_point(&self) -> Point {
-        match self {
-            Direction::Right => Point::new(1, 0),
-            Direction::Left => Point::new(-1, 0),
-            Direction::Down => Point::new(0, 1),
-            Direction::Up => Point::new(0, -1),
-        }
-    }
-}
+use aoclib::{polygon, Direction, Point};
 
 #[derive(Debug)]
 struct Command {
@@ -27,16 +8,8 @@ struct Command {
 
 impl Command {
     fn from_line_part1(s: &str) -> Self {
-        use Direction::*;
-
         let mut fields = s.split_whitespace();
-        let direction = match fields.next().unwrap() {
-            "R" => Right,
-            "L" => Left,
-            "U" => Up,
-            "D" => Down,
-            other => panic!("unhandled direction {:?}", other),
-        };
+        let direction = fields.next().unwrap().parse::<Direction>().unwrap();
         let distance = fields.next().unwrap().parse().unwrap();
         Command {
             direction,
@@ -45,15 +18,9 @@ impl Command {
     }
 
     fn from_line_part2(s: &str) -> Self {
-        let hex = s.split('#').last().unwrap();
+        let hex = s.split('#').next_back().unwrap();
         let distance = usize::from_str_radix(&hex[0..5], 16).unwrap();
-        let direction = match &hex[5..6] {
-            "0" => Direction::Right,
-            "1" => Direction::Down,
-            "2" => Direction::Left,
-            "3" => Direction::Up,
-            other => panic!("unhandled {}", other),
-        };
+        let direction = Direction::from_digit(hex.chars().nth(5).unwrap()).unwrap();
         Command {
             direction,
             distance,
@@ -61,21 +28,14 @@ impl Command {
     }
 }
 
-fn area(commands: &[Command]) -> f64 {
-    let mut point = Point::new(0, 0);
-    let mut total_area = 0f64;
-    let mut perimeter = 0f64;
+fn area(commands: &[Command]) -> i128 {
+    let mut vertices = vec![Point::new(0, 0)];
     for command in commands {
-        let next = point + (command.direction.as_point() * command.distance as i64);
-        let area = (point.x * next.y) as f64 - (point.y * next.x) as f64;
-        perimeter += (next.x - point.x).abs() as f64 + (next.y - point.y).abs() as f64;
-        total_area += area;
-        point = next;
+        let last = vertices[vertices.len() - 1];
+        vertices.push(last + command.direction.as_point() * command.distance as i64);
     }
-    assert_eq!(point, Point::new(0, 0));
-    total_area /= 2.0;
-    total_area += perimeter / 2.0;
-    total_area + 1.0
+    assert_eq!(vertices[vertices.len() - 1], Point::new(0, 0));
+    polygon::lattice_points(&vertices)
 }
 
 fn part1(input: &str) {
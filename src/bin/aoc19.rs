@@ -1,3 +1,4 @@
+use aoclib::RangeSet;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character;
@@ -101,6 +102,14 @@ impl Comparison {
             BinOp::Lt => value < self.value,
         }
     }
+
+    /// Every rating which passes this comparison
+    fn matching(&self) -> RangeSet<i32> {
+        match self.op {
+            BinOp::Gt => RangeSet::from_range(self.value + 1..=*RATINGS.end()),
+            BinOp::Lt => RangeSet::from_range(*RATINGS.start()..=self.value - 1),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -197,7 +206,7 @@ impl Program {
                 ),
                 tag("}"),
             ),
-            |r| r.into_iter().map(|(s, v)| (s, v)).collect(),
+            |r| r.into_iter().collect(),
         )(s)
     }
 
@@ -229,126 +238,37 @@ impl Program {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct BoundRange {
-    inner: Vec<(i32, i32)>,
-}
-
-impl BoundRange {
-    fn new() -> Self {
-        BoundRange {
-            inner: vec![(1, 4000)],
-        }
-    }
-
-    fn len(&self) -> usize {
-        self.inner.iter().map(|&(l, u)| (u - l) as usize + 1).sum()
-    }
-
-    fn empty() -> Self {
-        BoundRange { inner: vec![] }
-    }
-
-    fn intersect_comparison(&self, comparison: &Comparison) -> Self {
-        let inner = self
-            .inner
-            .iter()
-            .filter_map(|&(l, u)| match comparison.op {
-                BinOp::Gt => {
-                    if comparison.value > u {
-                        None
-                    } else {
-                        Some((std::cmp::max(comparison.value, l), u))
-                    }
-                }
-                BinOp::Lt => {
-                    if comparison.value < l {
-                        None
-                    } else {
-                        Some((l, std::cmp::min(comparison.value - 1, u)))
-                    }
-                }
-            })
-            .collect();
-        Self { inner }
-    }
-
-    fn subtract_comparison(&self, comparison: &Comparison) -> Self {
-        match comparison.op {
-            BinOp::Gt => {
-                // (1, 4000) - (> 3000) -> (1, 3000)
-                // (2500, 3500) - (> 3000) -> (2500, 3000)
-                // (3500, 4000) - (> 3000) -> None
-                let inner = self
-                    .inner
-                    .iter()
-                    .filter_map(|(lower, upper)| {
-                        if *lower > comparison.value {
-                            None
-                        } else {
-                            Some((*lower, std::cmp::min(comparison.value, *upper)))
-                        }
-                    })
-                    .collect();
-                BoundRange { inner }
-            }
-            BinOp::Lt => {
-                // (1, 4000) - (<4000) -> None
-                // (1, 4000) - (<2000) -> (2000, 4000)
-                // (2000, 4000) -  (<2000) -> (2000, 4000)
-                let inner = self
-                    .inner
-                    .iter()
-                    .filter_map(|(lower, upper)| {
-                        if *lower > comparison.value {
-                            None
-                        } else {
-                            Some((std::cmp::max(comparison.value, *lower), *upper))
-                        }
-                    })
-                    .collect();
-                BoundRange { inner }
-            }
-        }
-    }
-}
-
-impl std::fmt::Display for BoundRange {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        write!(f, "[ ")?;
-        for (l, u) in self.inner.iter() {
-            write!(f, "{}-{} ", l, u)?;
-        }
-        write!(f, "]")
-    }
-}
+const RATINGS: std::ops::RangeInclusive<i32> = 1..=4000;
 
 #[derive(Debug, Clone)]
 struct Constraints {
-    inner: BTreeMap<Field, BoundRange>,
+    inner: BTreeMap<Field, RangeSet<i32>>,
 }
 
 impl Constraints {
     fn new() -> Self {
         let mut c = BTreeMap::new();
-        c.insert(Field::A, BoundRange::new());
-        c.insert(Field::S, BoundRange::new());
-        c.insert(Field::M, BoundRange::new());
-        c.insert(Field::X, BoundRange::new());
+        c.insert(Field::A, RangeSet::from_range(RATINGS));
+        c.insert(Field::S, RangeSet::from_range(RATINGS));
+        c.insert(Field::M, RangeSet::from_range(RATINGS));
+        c.insert(Field::X, RangeSet::from_range(RATINGS));
         Constraints { inner: c }
     }
 
     fn empty() -> Self {
         let mut c = BTreeMap::new();
-        c.insert(Field::A, BoundRange::empty());
-        c.insert(Field::S, BoundRange::empty());
-        c.insert(Field::M, BoundRange::empty());
-        c.insert(Field::X, BoundRange::empty());
+        c.insert(Field::A, RangeSet::new());
+        c.insert(Field::S, RangeSet::new());
+        c.insert(Field::M, RangeSet::new());
+        c.insert(Field::X, RangeSet::new());
         Constraints { inner: c }
     }
 
     fn len(&self) -> usize {
-        self.inner.values().map(|v| v.len()).product()
+        self.inner
+            .values()
+            .map(|v| v.len().expect("too many ratings to count"))
+            .product()
     }
 
     fn subtract_comparison(&self, comparison: &Option<Comparison>) -> Self {
@@ -356,7 +276,7 @@ impl Constraints {
             let mut new = self.clone();
             new.inner.insert(
                 comparison.field,
-                self.inner[&comparison.field].subtract_comparison(comparison),
+                self.inner[&comparison.field].difference(&comparison.matching()),
             );
             new
         } else {
@@ -371,7 +291,7 @@ impl Constraints {
                 .iter()
                 .map(|(k, v)| {
                     if *k == comparison.field {
-                        (*k, v.intersect_comparison(comparison))
+                        (*k, v.intersection(&comparison.matching()))
                     } else {
                         (*k, v.clone())
                     }
@@ -1,7 +1,5 @@
-use aoclib::{DenseGrid, Point};
-use ndarray::prelude::*;
-use ndarray_linalg::Solve;
-use std::collections::BTreeSet;
+use aoclib::{linalg, DenseGrid, Point};
+use num::{BigRational, ToPrimitive};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 enum Tile {
@@ -22,42 +20,25 @@ impl Tile {
     }
 }
 
-const DIRS: [Point<i64>; 4] = [
-    Point::new(0, 1),
-    Point::new(1, 0),
-    Point::new(-1, 0),
-    Point::new(0, -1),
-];
-
-fn append_neighbors(g: &DenseGrid<Tile>, p: Point, o: &mut BTreeSet<Point>) {
-    for dir in DIRS {
-        match g.get(p + dir) {
-            Some(Tile::Garden) | Some(Tile::Start) => {
-                o.insert(p + dir);
-            }
-            _ => {}
-        }
-    }
+fn distances(g: &DenseGrid<Tile>) -> DenseGrid<Option<usize>> {
+    let starts = g
+        .iter()
+        .filter(|(_, tile)| *tile == Tile::Start)
+        .map(|(point, _)| point);
+    g.distance_field(starts, |tile| *tile != Tile::Rock)
 }
 
-fn part1(g: &DenseGrid<Tile>, steps: usize) -> BTreeSet<Point> {
-    let mut wave = BTreeSet::new();
-    for (point, tile) in g.iter() {
-        if tile == Tile::Start {
-            wave.insert(point);
-        }
-    }
-    for _ in 0..steps {
-        let mut next_wave = BTreeSet::new();
-        for point in &wave {
-            append_neighbors(g, *point, &mut next_wave);
-        }
-        wave = next_wave;
-    }
-    wave
+/// The number of cells which can be reached in exactly `steps` steps. Since the
+/// walker can always step back and forth, that is every cell at most `steps` away
+/// with the same parity.
+fn reachable(field: &DenseGrid<Option<usize>>, steps: usize) -> usize {
+    field
+        .iter()
+        .filter(|(_, distance)| distance.is_some_and(|d| d <= steps && d % 2 == steps % 2))
+        .count()
 }
 
-fn part2(map: &DenseGrid<Tile>, steps: usize) -> usize {
+fn part2(map: &DenseGrid<Tile>, steps: usize) -> anyhow::Result<usize> {
     let mut duplicated = DenseGrid::new_with(
         Point::new(-3 * map.width() as i64, -3 * map.height() as i64),
         Point::new(3 * map.max_x, 3 * map.max_y),
@@ -85,28 +66,31 @@ fn part2(map: &DenseGrid<Tile>, steps: usize) -> usize {
             }
         }
     }
-    let point1 = part1(&duplicated, 65);
-    let point2 = part1(&duplicated, 131 + 65);
-    let point3 = part1(&duplicated, 131 * 2 + 65);
-    let a: Array2<f64> = array![[0.0, 0.0, 1.0], [1.0, 1.0, 1.0], [4.0, 2.0, 1.0],];
-    let b: Array1<f64> = array![
-        point1.len() as f64,
-        point2.len() as f64,
-        point3.len() as f64
-    ];
-    let x = a.solve_into(b).unwrap();
-    let n = steps / 131;
-    let x0 = x[0] as usize;
-    let x1 = x[1] as usize;
-    let x2 = x[2] as usize;
-    x0 * n * n + x1 * n + x2
+    let field = distances(&duplicated);
+    let point1 = reachable(&field, 65);
+    let point2 = reachable(&field, 131 + 65);
+    let point3 = reachable(&field, 131 * 2 + 65);
+    // fit a quadratic through the three samples, which are 0, 1 and 2 tiles out
+    let x = linalg::solve_integers(
+        &[vec![0, 0, 1], vec![1, 1, 1], vec![4, 2, 1]],
+        &[point1, point2, point3],
+    )?;
+    let n = BigRational::from_integer((steps / 131).into());
+    let total = &x[0] * &n * &n + &x[1] * &n + &x[2];
+    if !total.is_integer() {
+        anyhow::bail!("quadratic fit gave a fractional count {}", total);
+    }
+    total
+        .to_integer()
+        .to_usize()
+        .ok_or_else(|| anyhow::anyhow!("quadratic fit gave a negative count {}", total))
 }
 
 fn main() -> anyhow::Result<()> {
     let stdin = std::io::stdin();
     let input = std::io::read_to_string(stdin)?;
     let map = DenseGrid::from_input(&input, Tile::from_char);
-    println!("part 1: {}", part1(&map, 64).len());
-    println!("part 2: {}", part2(&map, 26501365));
+    println!("part 1: {}", reachable(&distances(&map), 64));
+    println!("part 2: {}", part2(&map, 26501365)?);
     Ok(())
 }
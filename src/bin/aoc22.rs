@@ -1,11 +1,10 @@
-use aoclib::Vec3;
+use aoclib::{Cuboid, DenseGrid, Vec3};
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Block {
     index: usize,
-    lower: Vec3,
-    upper: Vec3,
+    cuboid: Cuboid,
 }
 
 fn poifi<'a, I: Iterator<Item = &'a str>>(i: &mut I) -> anyhow::Result<i64> {
@@ -20,65 +19,17 @@ impl Block {
             let mut rhs_i = rhs.split(',');
             let (lx, ly, lz) = (poifi(&mut lhs_i)?, poifi(&mut lhs_i)?, poifi(&mut lhs_i)?);
             let (rx, ry, rz) = (poifi(&mut rhs_i)?, poifi(&mut rhs_i)?, poifi(&mut rhs_i)?);
-            let mut lhs = Vec3::new(lx, ly, lz);
-            let mut rhs = Vec3::new(rx, ry, rz);
-            if rz < lz {
-                (lhs, rhs) = (rhs, lhs);
-            }
             Ok(Block {
                 index,
-                lower: lhs,
-                upper: rhs,
+                cuboid: Cuboid::new(Vec3::new(lx, ly, lz), Vec3::new(rx, ry, rz)),
             })
         } else {
             anyhow::bail!("unhandled input in {}", s);
         }
     }
 
-    #[inline(always)]
-    fn min_x(&self) -> i64 {
-        std::cmp::min(self.lower.x, self.upper.x)
-    }
-
-    #[inline(always)]
-    fn min_y(&self) -> i64 {
-        std::cmp::min(self.lower.y, self.upper.y)
-    }
-
-    #[inline(always)]
-    fn min_z(&self) -> i64 {
-        std::cmp::min(self.lower.z, self.upper.z)
-    }
-
-    #[inline(always)]
-    fn max_x(&self) -> i64 {
-        std::cmp::max(self.lower.x, self.upper.x)
-    }
-
-    #[inline(always)]
-    fn max_y(&self) -> i64 {
-        std::cmp::max(self.lower.y, self.upper.y)
-    }
-
-    #[inline(always)]
-    fn max_z(&self) -> i64 {
-        std::cmp::max(self.lower.z, self.upper.z)
-    }
-
-    fn drop_to(&mut self, z: i64) {
-        let distance = self.min_z() - z;
-        let vec = Vec3::new(0, 0, -1 * distance);
-        self.lower += vec;
-        self.upper += vec;
-    }
-
     fn occludes(&self, other: &Block) -> bool {
-        if self.min_z() > other.min_z() {
-            return false;
-        }
-        let x_ok = other.max_x() >= self.min_x() && other.min_x() <= self.max_x();
-        let y_ok = other.max_y() >= self.min_y() && other.min_y() <= self.max_y();
-        x_ok && y_ok
+        self.cuboid.min.z <= other.cuboid.min.z && self.cuboid.footprint_overlaps(&other.cuboid)
     }
 }
 
@@ -105,31 +56,29 @@ impl Problem {
                 if candidate == block {
                     continue;
                 }
-                if block.occludes(&candidate) {
+                if block.occludes(candidate) {
                     let child_graph_index =
                         block_index_to_graph_index.get(&candidate.index).unwrap();
                     occlusion.add_edge(*parent_graph_index, *child_graph_index, ());
                 }
             }
         }
+        let floor = blocks
+            .values()
+            .map(|b| b.cuboid.footprint())
+            .reduce(|a, b| a.union(&b))
+            .unwrap();
+        let mut heights = DenseGrid::new_with(floor.min, floor.max, 0);
         let mut visitor = petgraph::visit::Topo::new(&occlusion);
         while let Some(graph_index) = visitor.next(&occlusion) {
-            let incoming = occlusion
-                .neighbors_directed(graph_index, petgraph::Direction::Incoming)
-                .map(|n| {
-                    let block_index = occlusion.node_weight(n).unwrap();
-                    blocks[block_index].max_z()
-                })
-                .max()
-                .unwrap_or(0);
             if let Some(me) = occlusion
                 .node_weight(graph_index)
                 .and_then(|u| blocks.get_mut(u))
             {
-                me.drop_to(incoming + 1);
+                me.cuboid = me.cuboid.settle_onto(&mut heights);
             }
         }
-        let mut supported_by = BTreeMap::new();
+        let mut supported_by: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
         for (child_u, child_block) in blocks.iter() {
             let child_graph_index = block_index_to_graph_index.get(&child_block.index).unwrap();
             for parent_graph_index in
@@ -139,19 +88,15 @@ impl Problem {
                     continue;
                 }
                 let u = occlusion.node_weight(parent_graph_index).unwrap();
-                let block = &blocks[u];
-                if block.max_z() + 1 == child_block.min_z() {
-                    supported_by
-                        .entry(*child_u)
-                        .or_insert_with(Vec::new)
-                        .push(*u);
+                if child_block.cuboid.rests_on(&blocks[u].cuboid) {
+                    supported_by.entry(*child_u).or_default().push(*u);
                 }
             }
         }
         let mut supporting: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
         for (block, supporters) in supported_by.iter() {
             for k in supporters {
-                supporting.entry(*k).or_insert_with(Vec::new).push(*block);
+                supporting.entry(*k).or_default().push(*block);
             }
         }
         Self {
@@ -195,7 +140,7 @@ impl Problem {
             }
         }
         removed.remove(&root);
-        return removed.len();
+        removed.len()
     }
 
     fn part2(&self) -> usize {
@@ -1,6 +1,6 @@
-use aoclib::{Point, Vec3};
-use ndarray::prelude::*;
-use ndarray_linalg::Solve;
+use aoclib::geom3::Ray3;
+use aoclib::{linalg, PointN, Vec3};
+use num::{BigRational, Signed, ToPrimitive};
 use std::collections::BTreeSet;
 
 #[derive(Debug, Clone)]
@@ -31,100 +31,69 @@ impl Hailstone {
         }
     }
 
-    fn intersection_2d(&self, other: &Hailstone) -> Option<Point<f64>> {
-        if self.velocity == other.velocity {
+    fn path(&self) -> Ray3 {
+        Ray3::new(self.position, self.velocity)
+    }
+
+    /// Where the paths of the two hailstones cross in x and y, if both reach
+    /// that point strictly in the future
+    fn intersection_2d(&self, other: &Hailstone) -> Option<(BigRational, BigRational)> {
+        let (t, s) = self.path().crossing_xy(&other.path())?;
+        if !t.is_positive() || !s.is_positive() {
             return None;
         }
-        // given self.position = <a, b, _> and self.velocity = <q, w>
-        // and other.position = <c, d> and other.velocity = <x, y>
-        // we have a + qt = c + xs
-        // and= b + wt = d + ys
-        // solve for t, s
-        // qt - xs = c - a
-        // wc - ys = d - b
-
-        let a = self.position.x as f64;
-        let b = self.position.y as f64;
-        let q = self.velocity.x as f64;
-        let w = self.velocity.y as f64;
-        let c = other.position.x as f64;
-        let d = other.position.y as f64;
-        let x = other.velocity.x as f64;
-        let y = other.velocity.y as f64;
-
-        let matx: Array2<f64> = array![[q, -1.0 * x], [w, -1.0 * y]];
-        let vec: Array1<f64> = array![c - a, d - b];
-        match matx.solve_into(vec) {
-            Ok(solution) => {
-                let t = solution[0];
-                let s = solution[1];
-                if t > 0.0 && s > 0.0 {
-                    Some(Point::new(a + q * t, b + w * t))
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        }
+        let [x, y, _] = self.path().at(&t);
+        Some((x, y))
     }
 }
 
+fn exact(value: i64) -> BigRational {
+    BigRational::from_integer(value.into())
+}
+
 fn all_pairs<T: Clone>(slice: &[T]) -> impl Iterator<Item = (&T, &T)> {
     (0..slice.len()).flat_map(move |i| ((i + 1)..slice.len()).map(move |j| (&slice[i], &slice[j])))
 }
 
 fn part1(hailstones: &[Hailstone]) -> usize {
-    let (min_x, max_x): (f64, f64) = if hailstones.len() == 5 {
-        (7.0, 27.0)
+    let (min, max) = if hailstones.len() == 5 {
+        (exact(7), exact(27))
     } else {
-        (200_000_000_000_000.0, 400_000_000_000_000.0)
+        (exact(200_000_000_000_000), exact(400_000_000_000_000))
     };
+    let test_area = min..=max;
     all_pairs(hailstones)
-        .filter_map(|(lhs, rhs)| match lhs.intersection_2d(rhs) {
-            Some(pt) if pt.x >= min_x && pt.y >= min_x && pt.x <= max_x && pt.y <= max_x => {
-                Some(pt)
-            }
-            _ => None,
-        })
+        .filter_map(|(lhs, rhs)| lhs.intersection_2d(rhs))
+        .filter(|(x, y)| test_area.contains(x) && test_area.contains(y))
         .count()
 }
 
-fn initial_velocity_range(min: i64, max: i64) -> BTreeSet<i64> {
-    (min..=max).collect()
+/// The only rock velocity along `axis` which can hit every pair of hailstones that
+/// share a velocity along that axis
+fn rock_velocity_along(hailstones: &[Hailstone], axis: usize) -> i64 {
+    let candidates = all_pairs(hailstones)
+        .filter_map(|(h1, h2)| {
+            let (v1, v2) = (PointN::from(h1.velocity), PointN::from(h2.velocity));
+            let delta = PointN::from(h2.position) - PointN::from(h1.position);
+            (v1[axis] == v2[axis]).then_some((delta[axis], v1[axis]))
+        })
+        .fold(
+            (-500..=500).collect::<BTreeSet<i64>>(),
+            |mut vels, (delta, hail_vel)| {
+                vels.retain(|vel| *vel != hail_vel && delta % (vel - hail_vel) == 0);
+                vels
+            },
+        );
+    assert_eq!(candidates.len(), 1);
+    *candidates.first().unwrap()
 }
 
 fn part2(hailstones: &[Hailstone]) -> i64 {
     // had to look up hints for this one; linear algebra was an 8am class 15 years ago
     // and unlike graph theory and general algorithms, has never ever come up professionally
-    let x_velocity = all_pairs(hailstones)
-        .filter(|(h1, h2)| h1.velocity.x == h2.velocity.x)
-        .fold(initial_velocity_range(-500, 500), |mut vels, (h1, h2)| {
-            let delta_x = h2.position.x - h1.position.x;
-            vels.retain(|xvel| *xvel != h1.velocity.x && delta_x % (xvel - h1.velocity.x) == 0);
-            vels
-        });
-    let y_velocity = all_pairs(hailstones)
-        .filter(|(h1, h2)| h1.velocity.y == h2.velocity.y)
-        .fold(initial_velocity_range(-500, 500), |mut vels, (h1, h2)| {
-            let delta_y = h2.position.y - h1.position.y;
-            vels.retain(|yvel| *yvel != h1.velocity.y && delta_y % (yvel - h1.velocity.y) == 0);
-            vels
-        });
-    let z_velocity = all_pairs(hailstones)
-        .filter(|(h1, h2)| h1.velocity.z == h2.velocity.z)
-        .fold(initial_velocity_range(-500, 500), |mut vels, (h1, h2)| {
-            let delta_z = h2.position.z - h1.position.z;
-            vels.retain(|zvel| *zvel != h1.velocity.z && delta_z % (zvel - h1.velocity.z) == 0);
-            vels
-        });
-    assert_eq!(x_velocity.len(), 1);
-    assert_eq!(y_velocity.len(), 1);
-    assert_eq!(z_velocity.len(), 1);
-    let velocity = Vec3::new(
-        *x_velocity.first().unwrap(),
-        *y_velocity.first().unwrap(),
-        *z_velocity.first().unwrap(),
-    );
+    let velocity = Vec3::from(PointN::<i64, 3>::from_fn(|axis| {
+        rock_velocity_along(hailstones, axis)
+    }));
 
     // assume that the 2d intersection is sufficient to uniquely identify the thrown rock;
     // otherwise
@@ -137,49 +106,33 @@ fn part2(hailstones: &[Hailstone]) -> i64 {
     // (H[0].v.x - V.x) * t + 0s - R.x + 0= -H[0].p.x
     // (H[0].v.y - V.y) * t + 0s + 0 - R.y = -H[0].p.y
 
-    let matx: Array2<f64> = array![
-        [
-            (hailstones[0].velocity.x - velocity.x) as f64,
-            0.0f64,
-            -1.0f64,
-            0.0f64
-        ],
-        [
-            (hailstones[0].velocity.y - velocity.y) as f64,
-            0.0,
-            0.0,
-            -1.0
-        ],
-        [
-            0.0,
-            (hailstones[1].velocity.x - velocity.x) as f64,
-            -1.0,
-            0.0
+    let (h0, h1) = (&hailstones[0], &hailstones[1]);
+    let solution = linalg::solve_integers(
+        &[
+            vec![h0.velocity.x - velocity.x, 0, -1, 0],
+            vec![h0.velocity.y - velocity.y, 0, 0, -1],
+            vec![0, h1.velocity.x - velocity.x, -1, 0],
+            vec![0, h1.velocity.y - velocity.y, 0, -1],
         ],
-        [
-            0.0,
-            (hailstones[1].velocity.y - velocity.y) as f64,
-            0.0,
-            -1.0
+        &[
+            -h0.position.x,
+            -h0.position.y,
+            -h1.position.x,
+            -h1.position.y,
         ],
-    ];
-    let matb: Array1<f64> = array![
-        -1.0 * hailstones[0].position.x as f64,
-        -1.0 * hailstones[0].position.y as f64,
-        -1.0 * hailstones[1].position.x as f64,
-        -1.0 * hailstones[1].position.y as f64
-    ];
-
-    let solv = matx.solve_into(matb).unwrap();
-    let t = solv[0];
-    let px = solv[2].round();
-    let py = solv[3].round();
+    )
+    .expect("the first two hailstones pin down the rock");
+    let t = &solution[0];
+    let px = &solution[2];
+    let py = &solution[3];
 
     // given the time, x, and y coordinate, the z coordinate is pretty straightforward
 
-    let pz = hailstones[0].position.z as f64 + (hailstones[0].velocity.z - velocity.z) as f64 * t;
+    let pz = exact(h0.position.z) + exact(h0.velocity.z - velocity.z) * t;
 
-    (px + py + pz) as i64
+    let total = px + py + pz;
+    assert!(total.is_integer(), "rock starts at a fractional position");
+    total.to_integer().to_i64().unwrap()
 }
 
 fn main() -> anyhow::Result<()> {
@@ -1,7 +1,7 @@
 use clap::{Parser, ValueEnum};
 use std::io::Read;
 
-use aoclib::Point;
+use aoclib::{Point, Rect};
 
 #[derive(ValueEnum, Debug, PartialEq, Eq, Clone, Copy)]
 enum Mode {
@@ -26,9 +26,12 @@ struct Number {
 
 impl Number {
     fn is_adjacent_to(&self, sym: &Symbol) -> bool {
-        let y = sym.loc.y >= (self.row - 1) && sym.loc.y <= self.row + 1;
-        let x = sym.loc.x >= (self.col_start - 1) && sym.loc.x <= self.col_end + 1;
-        y && x
+        Rect::new(
+            Point::new(self.col_start, self.row),
+            Point::new(self.col_end, self.row),
+        )
+        .expand(1)
+        .contains(sym.loc)
     }
 }
 
@@ -1,3 +1,4 @@
+use aoclib::RangeMap;
 use itertools::Itertools;
 use nom::bytes::complete::tag;
 use nom::character;
@@ -10,87 +11,13 @@ use petgraph::graph::{DiGraph, NodeIndex};
 
 use std::collections::BTreeMap;
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
-struct RangeMapEntry {
-    start_id: u64,
-    length: u64,
-    target_id: u64,
-}
-
-impl RangeMapEntry {
-    fn translate(&self, value: u64) -> u64 {
-        assert!(value >= self.start_id);
-        if value > self.end() {
-            value
-        } else {
-            let offset = value - self.start_id;
-            self.target_id + offset
-        }
-    }
-
-    fn end(&self) -> u64 {
-        self.start_id + self.length
-    }
-}
-
-#[derive(Debug)]
-struct RangeMap {
-    source_to_dest: Vec<RangeMapEntry>,
-}
-
-impl RangeMap {
-    // translate a contiguous range into one or more contiguous ranges of outputs
-    fn translate_range(&self, mut start: u64, mut length: u64) -> Vec<(u64, u64)> {
-        let mut output = Vec::new();
-        let first = self.source_to_dest[0].start_id;
-        while length > 0 {
-            let pp = self.source_to_dest.partition_point(|v| v.start_id <= start);
-            let (new_start, len) = if pp == 0 {
-                // we're before the first mapped segment
-                assert!(start < first);
-                let remaining_before_start = first - start;
-                let this_chunk_len = std::cmp::min(length, remaining_before_start);
-                (start, this_chunk_len)
-            } else {
-                let segment = &self.source_to_dest[pp - 1];
-                if start < segment.end() {
-                    // we're in the segment
-                    let remaining = segment.end() - start;
-                    let this_chunk_len = std::cmp::min(length, remaining);
-                    (segment.translate(start), this_chunk_len)
-                } else {
-                    // we're outside the segment
-                    let remaining = if pp == self.source_to_dest.len() {
-                        length
-                    } else {
-                        let next = &self.source_to_dest[pp];
-                        next.start_id - start
-                    };
-                    let this_chunk_len = std::cmp::min(length, remaining);
-                    (start, this_chunk_len)
-                }
-            };
-            output.push((new_start, len));
-            start += len;
-            length -= len;
-        }
-        output
-    }
-
-    fn from(raw: Vec<(u64, u64, u64)>) -> Self {
-        let mut source_to_dest = raw
-            .into_iter()
-            .map({
-                |(target_id, start_id, length)| RangeMapEntry {
-                    start_id,
-                    length,
-                    target_id,
-                }
-            })
-            .collect::<Vec<_>>();
-        source_to_dest.sort();
-        RangeMap { source_to_dest }
-    }
+fn range_map(raw: Vec<(u64, u64, u64)>) -> RangeMap {
+    RangeMap::from_ranges(raw.into_iter().map(|(target_id, start_id, length)| {
+        (
+            start_id as i64..(start_id + length) as i64,
+            target_id as i64,
+        )
+    }))
 }
 
 #[derive(Debug)]
@@ -137,7 +64,7 @@ impl Almanac {
                 let maps = ranges
                     .into_iter()
                     .map(|((from, to), map): ((&str, &str), Vec<_>)| {
-                        ((from.to_string(), to.to_string()), RangeMap::from(map))
+                        ((from.to_string(), to.to_string()), range_map(map))
                     })
                     .collect::<BTreeMap<(String, String), RangeMap>>();
 
@@ -180,39 +107,17 @@ impl Almanac {
         path.unwrap().1
     }
 
-    fn location_for_seed(&self, seed: u64) -> u64 {
+    /// Chain every map along the path from seeds to locations into one
+    fn seed_to_location(&self) -> RangeMap {
         self.seed_to_location_path()
             .into_iter()
             .tuple_windows()
-            .fold(seed, |value, (from, to)| {
+            .fold(RangeMap::identity(), |composed, (from, to)| {
                 let from_name = self.nodes_rev[&from].clone();
                 let to_name = self.nodes_rev[&to].clone();
-                let map = &self.maps[&(from_name, to_name)];
-                map.translate_range(value, 1)[0].0
+                composed.then(&self.maps[&(from_name, to_name)])
             })
     }
-
-    fn lowest_for_seed_range(&self, start: u64, length: u64) -> u64 {
-        // we model this as a series of flows between segments, which may split or recombine
-        let wavefrom = vec![(start, length)];
-        let final_wave = self
-            .seed_to_location_path()
-            .into_iter()
-            .tuple_windows()
-            .fold(wavefrom, |wave, (from, to)| {
-                let from_name = self.nodes_rev[&from].clone();
-                let to_name = self.nodes_rev[&to].clone();
-                let map = &self.maps[&(from_name, to_name)];
-                // TODO: deduplicate wavefronts? theoretically we could
-                // get a combinatorial explosion here, but in practice we
-                // don't seem to get more than 32 waves live at a time.
-                wave.into_iter()
-                    .flat_map(|(start, length)| map.translate_range(start, length))
-                    .collect::<Vec<_>>()
-            });
-        assert!(final_wave.iter().map(|(_, l)| *l).sum::<u64>() == length);
-        final_wave.into_iter().map(|(s, _)| s).min().unwrap()
-    }
 }
 
 fn main() -> anyhow::Result<()> {
@@ -222,17 +127,20 @@ fn main() -> anyhow::Result<()> {
     if !remainder.is_empty() {
         anyhow::bail!("unparsed input: {:?}", remainder);
     }
+    let seed_to_location = almanac.seed_to_location();
     let res1 = almanac
         .seeds
         .iter()
-        .map(|seed| almanac.location_for_seed(*seed))
+        .map(|seed| seed_to_location.get(*seed as i64))
         .min();
     println!("part1: {:?}", res1);
     let res2 = almanac
         .seeds
         .iter()
         .tuples()
-        .map(|(seed_from, length)| almanac.lowest_for_seed_range(*seed_from, *length))
+        .filter_map(|(seed_from, length)| {
+            seed_to_location.min_over(*seed_from as i64..(seed_from + length) as i64)
+        })
         .min();
     println!("part2: {:?}", res2);
     Ok(())